@@ -0,0 +1,763 @@
+use std::path::{Path, PathBuf};
+use std::error::Error;
+use std::fs::{OpenOptions, File};
+use std::io::{self, Read, Write, SeekFrom, Seek, BufReader};
+use std::fs;
+use crate::core::{AddOptions, ResourceEntry, findResources, readResource, resourceDecoder, copyResource, readResourceHead, writeResourceFrom, checkResourceInfo, moveData, alignmentPadding, versionCompatibility, VersionCompatibility, MAX_ALIGNMENT, BUFFER_SIZE};
+use crate::util::{listFiles, safeRelativePath};
+use crate::index::ResourceIndex;
+use crate::error::{AppenderError, isVersionMismatch, withContext};
+use crate::crypto::Secret;
+use crate::pe::{PeImage, CertificateTable, SignatureMode, CERTIFICATE_ALIGNMENT};
+use crate::format::{ImageInfo, CarrierFormat};
+use crate::reader::ResourceReader;
+#[cfg(feature = "mmap")]
+use crate::mmap::MappedCarrier;
+use std::sync::Mutex;
+
+lazy_static! {
+    /// 当前程序的资源条目缓存(资源条目, 资源数据结束位置)
+    static ref CURRENT_EXE_ENTRIES: Mutex<Option<(Vec<ResourceEntry>, u64)>> = Mutex::new(None);
+}
+
+/// 释放多个资源时，输出文件已存在或资源文件名重复的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ConflictPolicy {
+    /// 覆盖已存在的文件(资源文件名重复时保留最后一个资源)
+    #[default]
+    Overwrite,
+    /// 跳过该资源
+    Skip,
+    /// 在文件名后添加序号，如 "file (1).txt"
+    Rename,
+    /// 报错，不输出任何文件
+    Error,
+}
+
+impl ConflictPolicy {
+    /// 所有处理方式名称
+    pub const NAMES: [&'static str; 4] = ["overwrite", "skip", "rename", "error"];
+
+    /// 由处理方式名称解析
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "overwrite" => Some(ConflictPolicy::Overwrite),
+            "skip" => Some(ConflictPolicy::Skip),
+            "rename" => Some(ConflictPolicy::Rename),
+            "error" => Some(ConflictPolicy::Error),
+            _ => None,
+        }
+    }
+}
+
+/// 释放多个资源的结果
+#[derive(Debug, Clone, Default)]
+pub struct ExportReport {
+    /// 实际输出的文件路径
+    pub exported: Vec<PathBuf>,
+    /// 因输出文件已存在或资源文件名重复而跳过的资源(资源ID, 输出路径)，冲突处理方式为跳过时
+    pub skipped: Vec<(String, PathBuf)>,
+    /// 被之后资源文件名相同的资源覆盖而未输出的资源(资源ID, 输出路径)，冲突处理方式为覆盖时
+    pub overwritten: Vec<(String, PathBuf)>,
+}
+
+/// 载体文件(附加了资源的文件)
+pub struct Carrier {
+    /// 载体文件路径
+    path: PathBuf,
+    /// 载体文件(只读)
+    file: File,
+    /// 资源条目缓存
+    entries: Option<Vec<ResourceEntry>>,
+    /// 资源数据结束位置(资源索引起始位置)
+    dataEnd: u64,
+    /// 读取加密资源使用的密钥来源
+    secret: Option<Secret>,
+    /// 修改后保留 PE 文件头中的校验和
+    keepChecksum: bool,
+}
+
+impl Carrier {
+    /// 打开载体文件
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref().to_path_buf();
+        let file = File::open(&path)?;
+        Ok(Carrier { path, file, entries: None, dataEnd: 0, secret: None, keepChecksum: false })
+    }
+
+    /// 打开当前程序(读取程序自身附加的资源)
+    ///
+    /// 资源条目在进程内缓存，多次打开时不会重复读取索引
+    pub fn current_exe() -> Result<Self, Box<dyn Error>> {
+        let mut carrier = Carrier::open(std::env::current_exe()?)?;
+        let mut cache = CURRENT_EXE_ENTRIES.lock().unwrap();
+        match cache.as_ref() {
+            Some((entries, dataEnd)) => {
+                carrier.entries = Some(entries.clone());
+                carrier.dataEnd = *dataEnd;
+            }
+            None => {
+                let entries = carrier.entries()?.to_vec();
+                *cache = Some((entries, carrier.dataEnd));
+            }
+        }
+        Ok(carrier)
+    }
+
+    /// 设置读取加密资源使用的密钥来源
+    pub fn set_secret(&mut self, secret: Option<Secret>) {
+        self.secret = secret;
+    }
+
+    /// 设置修改后是否保留 PE 文件头中的校验和(默认重新计算)
+    pub fn set_keep_checksum(&mut self, keepChecksum: bool) {
+        self.keepChecksum = keepChecksum;
+    }
+
+    /// 获取载体文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 获取所有资源条目
+    pub fn entries(&mut self) -> Result<&[ResourceEntry], Box<dyn Error>> {
+        if self.entries.is_none() {
+            let entries = match self.readIndex()? {
+                Some(entries) => entries,
+                // 没有资源索引的旧载体文件，扫描整个文件
+                None => {
+                    self.dataEnd = self.file.seek(SeekFrom::End(0))?;
+                    findResources(&mut self.file)?
+                }
+            };
+            self.entries = Some(entries);
+        }
+        Ok(self.entries.as_ref().unwrap())
+    }
+
+    /// 通过文件末尾的资源索引读取资源条目
+    /// # 返回值
+    /// 资源条目，没有索引或索引与资源不符时返回 None；资源由新的主版本写入时返回版本不兼容的错误
+    fn readIndex(&mut self) -> Result<Option<Vec<ResourceEntry>>, Box<dyn Error>> {
+        let (indexOffset, index) = match ResourceIndex::read(&mut self.file)? {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let mut entries = Vec::with_capacity(index.Entries.len());
+        for indexEntry in index.Entries {
+            let (head, headLen) = match readResourceHead(&mut self.file, indexEntry.Offset) {
+                Ok(head) => head,
+                // 新的主版本写入的资源无法读取，不能当作没有资源
+                Err(e) if isVersionMismatch(&*e) => return Err(e),
+                Err(_e) => return Ok(None),
+            };
+            if head.getId() != indexEntry.Id || head.getLength() != indexEntry.Length || headLen != indexEntry.HeadLength {
+                return Ok(None);
+            }
+            entries.push(ResourceEntry { offset: indexEntry.Offset, headLen: indexEntry.HeadLength, head });
+        }
+        // 索引前可能有对齐填充，资源数据结束位置以最后一个资源为准
+        self.dataEnd = entries.iter().map(|entry| entry.nextOffset()).max().unwrap_or(indexOffset);
+        Ok(Some(entries))
+    }
+
+    /// 识别载体文件格式(PE、ELF、Mach-O)
+    pub fn image_info(&mut self) -> ImageInfo {
+        ImageInfo::detect(&mut self.file)
+    }
+
+    /// 获取已存在的其他附加数据(Overlay，如安装包数据等，不包括数字签名)
+    /// # 返回值
+    /// (起始位置, 长度)，无法识别文件格式或附加数据全部为资源时返回 None
+    pub fn foreign_overlay(&mut self) -> Result<Option<(u64, u64)>, Box<dyn Error>> {
+        let info = self.image_info();
+        if info.format == CarrierFormat::Raw {
+            return Ok(None);
+        }
+        let end = self.dataStart()?;
+        let start = self.overlayStart(end)?;
+        Ok(if end > start { Some((start, end - start)) } else { None })
+    }
+
+    /// 附加数据起始位置(映像结束位置，紧跟映像的证书表不属于其他附加数据，嵌入资源后证书表会延伸到资源之后)
+    /// # 参数
+    /// 1. 资源数据起始位置
+    fn overlayStart(&mut self, dataStart: u64) -> Result<u64, Box<dyn Error>> {
+        let mut start = self.image_info().imageEnd;
+        if let Some(image) = PeImage::parse(&mut self.file)? {
+            if let Some(table) = image.certificateTable(&mut self.file)? {
+                if table.address == start {
+                    start = table.end().min(dataStart);
+                }
+            }
+        }
+        Ok(start)
+    }
+
+    /// 资源数据起始位置(第一个资源的位置，没有资源时为资源数据结束位置)
+    fn dataStart(&mut self) -> Result<u64, Box<dyn Error>> {
+        let firstOffset = self.entries()?.iter().map(|entry| entry.offset).min();
+        Ok(firstOffset.unwrap_or(self.dataEnd))
+    }
+
+    /// 根据资源ID寻找资源条目
+    pub fn find(&mut self, id: &str) -> Result<Option<ResourceEntry>, Box<dyn Error>> {
+        Ok(self.entries()?.iter().find(|entry| entry.head.getId().eq(id)).cloned())
+    }
+
+    /// 读取资源(解压后写入输出流)
+    /// # 返回值
+    /// 写出的字节数
+    pub fn read<W: Write>(&mut self, id: &str, writer: &mut W) -> Result<u64, Box<dyn Error>> {
+        let entry = self.find(id)?.ok_or_else(|| Box::<dyn Error>::from(AppenderError::NotFound("Resource not found".to_string())))?;
+        readResource(&mut self.file, &entry, writer, self.secret.as_ref())
+    }
+
+    /// 校验资源(检查尾部标识、解压并校验大小及摘要，不写出数据)
+    /// # 返回值
+    /// 原始资源大小
+    pub fn verify(&mut self, id: &str) -> Result<u64, Box<dyn Error>> {
+        self.read(id, &mut io::sink())
+    }
+
+    /// 打开资源(返回可随机访问的原始资源数据读取器，不释放到磁盘)
+    pub fn open_resource(&mut self, id: &str) -> Result<ResourceReader, Box<dyn Error>> {
+        let entry = self.find(id)?.ok_or_else(|| Box::<dyn Error>::from(AppenderError::NotFound("Resource not found".to_string())))?;
+        ResourceReader::new(File::open(&self.path)?, entry, self.secret.clone())
+    }
+
+    /// 内存映射载体文件(零拷贝读取未压缩且未加密的资源)
+    ///
+    /// 映射期间不应修改载体文件
+    #[cfg(feature = "mmap")]
+    pub fn map(&mut self) -> Result<MappedCarrier, Box<dyn Error>> {
+        let entries = self.entries()?.to_vec();
+        // 载体文件以只读方式打开，映射期间由调用方保证文件不被修改
+        let map = unsafe { memmap2::Mmap::map(&self.file)? };
+        Ok(MappedCarrier::new(map, entries))
+    }
+
+    /// 读取资源到内存
+    pub fn read_to_vec(&mut self, id: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut data = Vec::new();
+        self.read(id, &mut data)?;
+        Ok(data)
+    }
+
+    /// 释放资源到文件
+    /// # 参数
+    /// 1. 资源ID
+    /// 2. 输出路径(目录则保留原文件名)
+    /// # 返回值
+    /// 实际输出的文件路径
+    pub fn export(&mut self, id: &str, outputPath: &Path) -> Result<PathBuf, Box<dyn Error>> {
+        let entry = self.find(id)?.ok_or_else(|| Box::<dyn Error>::from(AppenderError::NotFound("Resource not found".to_string())))?;
+        let outputPath = if outputPath.is_dir() {
+            // 资源文件名可能包含相对路径(增加目录时)
+            let path = outputPath.join(safeName(&entry)?);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            path
+        } else {
+            outputPath.to_path_buf()
+        };
+
+        self.exportEntry(&entry, &outputPath)?;
+        Ok(outputPath)
+    }
+
+    /// 释放资源条目到文件(不再按资源ID查找，ID重复的旧载体文件也能释放指定的资源)
+    ///
+    /// 先检查资源并创建解码读取器(密钥缺失、尾部标识错误时不创建任何文件)，再写入同一目录下的临时文件，
+    /// 完整写出并校验通过后重命名为输出文件；释放失败时已存在的输出文件保持不变
+    fn exportEntry(&mut self, entry: &ResourceEntry, outputPath: &Path) -> Result<(), Box<dyn Error>> {
+        let reader = resourceDecoder(&mut self.file, entry, self.secret.as_ref())?;
+        let tempPath = temporaryPath(outputPath);
+        let result = File::create(&tempPath)
+            .map_err(Box::<dyn Error>::from)
+            .and_then(|mut tempFile| {
+                copyResource(reader, &entry.head, &mut tempFile)?;
+                tempFile.sync_all()?;
+                Ok(())
+            })
+            .and_then(|_| fs::rename(&tempPath, outputPath).map_err(Box::from));
+        if result.is_err() {
+            // 删除释放错误的临时文件
+            let _ = fs::remove_file(&tempPath);
+        }
+        result
+    }
+
+    /// 释放ID以指定前缀开头的所有资源(按资源文件名中的相对路径重建目录结构，覆盖已存在的文件)
+    /// # 参数
+    /// 1. 资源ID前缀(为空时释放所有资源)
+    /// 2. 输出目录
+    /// # 返回值
+    /// 实际输出的文件路径
+    pub fn export_directory(&mut self, prefix: &str, outputPath: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        self.export_directory_with(prefix, outputPath, ConflictPolicy::Overwrite)
+    }
+
+    /// 释放所有资源(按资源文件名输出，只读取一次资源索引)
+    /// # 参数
+    /// 1. 输出目录
+    /// 2. 输出文件已存在或资源文件名重复时的处理方式
+    /// # 返回值
+    /// 实际输出的文件路径(跳过的资源不包含在内)
+    pub fn export_all(&mut self, outputPath: &Path, policy: ConflictPolicy) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        self.export_directory_with("", outputPath, policy)
+    }
+
+    /// 按指定的冲突处理方式释放ID以指定前缀开头的所有资源
+    ///
+    /// 先确定所有输出路径再释放，冲突处理方式为报错时不会输出任何文件
+    /// # 参数
+    /// 1. 资源ID前缀(为空时释放所有资源)
+    /// 2. 输出目录
+    /// 3. 输出文件已存在或资源文件名重复时的处理方式
+    /// # 返回值
+    /// 实际输出的文件路径(跳过的资源不包含在内)
+    pub fn export_directory_with(&mut self, prefix: &str, outputPath: &Path, policy: ConflictPolicy) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+        Ok(self.export_directory_report(prefix, outputPath, policy)?.exported)
+    }
+
+    /// 按指定的冲突处理方式释放ID以指定前缀开头的所有资源，并返回每个资源的处理结果
+    ///
+    /// 先确定所有输出路径再释放，冲突处理方式为报错时不会输出任何文件；按资源条目释放，旧载体文件中ID重复的资源各自输出自己的数据
+    /// # 参数
+    /// 1. 资源ID前缀(为空时释放所有资源)
+    /// 2. 输出目录
+    /// 3. 输出文件已存在或资源文件名重复时的处理方式
+    pub fn export_directory_report(&mut self, prefix: &str, outputPath: &Path, policy: ConflictPolicy) -> Result<ExportReport, Box<dyn Error>> {
+        let entries: Vec<ResourceEntry> = self.entries()?.iter().filter(|entry| entry.head.getId().starts_with(prefix)).cloned().collect();
+
+        // 确定输出路径
+        let mut report = ExportReport::default();
+        let mut planned: Vec<(ResourceEntry, PathBuf)> = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let mut path = outputPath.join(safeName(&entry)?);
+            let conflicts = |path: &Path| path.exists() || planned.iter().any(|(_, planned)| planned == path);
+            if conflicts(&path) {
+                match policy {
+                    // 资源文件名重复时，之前的资源被之后的资源覆盖
+                    ConflictPolicy::Overwrite => {
+                        if let Some(position) = planned.iter().position(|(_, planned)| planned == &path) {
+                            let (overwritten, path) = planned.remove(position);
+                            report.overwritten.push((overwritten.head.getId().to_string(), path));
+                        }
+                    }
+                    ConflictPolicy::Skip => {
+                        report.skipped.push((entry.head.getId().to_string(), path));
+                        continue;
+                    }
+                    ConflictPolicy::Rename => path = (1..).map(|index| renamedPath(&path, index)).find(|path| !conflicts(path)).unwrap(),
+                    ConflictPolicy::Error => return Err(Box::from(AppenderError::Conflict(format!("The output file \"{}\" of resource \"{}\" already exists", path.display(), entry.head.getId())))),
+                }
+            }
+            planned.push((entry, path));
+        }
+
+        for (entry, path) in planned {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            self.exportEntry(&entry, &path)?;
+            report.exported.push(path);
+        }
+        Ok(report)
+    }
+
+    /// 增加资源
+    /// # 参数
+    /// 1. 资源文件
+    /// 2. 资源ID
+    /// 3. 压缩等级(可选，使用 gzip 压缩)
+    pub fn add(&mut self, sourceFilePath: &Path, id: &str, compressionGrade: Option<u32>) -> Result<(), Box<dyn Error>> {
+        self.add_with(sourceFilePath, id, &AddOptions::compression(compressionGrade))
+    }
+
+    /// 按选项增加资源
+    /// # 参数
+    /// 1. 资源文件
+    /// 2. 资源ID
+    /// 3. 增加资源选项
+    pub fn add_with(&mut self, sourceFilePath: &Path, id: &str, options: &AddOptions) -> Result<(), Box<dyn Error>> {
+        let mut options = options.clone();
+        options.name = Some(sourceName(sourceFilePath, &options));
+        self.add_from(&mut BufReader::with_capacity(BUFFER_SIZE, File::open(sourceFilePath)?), id, &options)
+    }
+
+    /// 从输入流增加资源
+    /// # 参数
+    /// 1. 资源数据输入流(如标准输入)
+    /// 2. 资源ID
+    /// 3. 增加资源选项(未指定资源文件名时使用资源ID)
+    pub fn add_from<R: Read>(&mut self, source: &mut R, id: &str, options: &AddOptions) -> Result<(), Box<dyn Error>> {
+        let mut entries = self.entries()?.to_vec();
+        if entries.iter().any(|entry| entry.head.getId().eq(id)) {
+            if options.replace {
+                return self.update_from(source, id, options);
+            }
+            return Err(Box::from(AppenderError::DuplicateId(format!("Resource id \"{}\" already exists, please use another id or replace it", id))));
+        }
+        let table = self.certificateTable(options.signature)?;
+        let mut targetFile = OpenOptions::new().read(true).write(true).open(&self.path)?;
+
+        // 移除旧的资源索引，在资源数据末尾写入新资源
+        self.entries = None;
+        targetFile.set_len(self.dataEnd)?;
+        targetFile.seek(SeekFrom::Start(self.dataEnd))?;
+        match writeResourceFrom(&mut targetFile, source, id, options.name.as_deref().unwrap_or(id), options) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => {
+                self.restore(&mut targetFile, entries, table, options.keepChecksum)?;
+                return Err(e);
+            }
+        }
+
+        self.writeIndex(&mut targetFile, entries, table)?;
+        self.finish(&mut targetFile, options.keepChecksum)
+    }
+
+    /// 替换资源
+    /// # 参数
+    /// 1. 资源文件
+    /// 2. 资源ID(必须已存在)
+    /// 3. 压缩等级(可选，使用 gzip 压缩)
+    pub fn update(&mut self, sourceFilePath: &Path, id: &str, compressionGrade: Option<u32>) -> Result<(), Box<dyn Error>> {
+        self.update_with(sourceFilePath, id, &AddOptions::compression(compressionGrade))
+    }
+
+    /// 按选项替换资源
+    /// # 参数
+    /// 1. 资源文件
+    /// 2. 资源ID(必须已存在)
+    /// 3. 增加资源选项
+    pub fn update_with(&mut self, sourceFilePath: &Path, id: &str, options: &AddOptions) -> Result<(), Box<dyn Error>> {
+        let mut options = options.clone();
+        options.name = Some(sourceName(sourceFilePath, &options));
+        self.update_from(&mut BufReader::with_capacity(BUFFER_SIZE, File::open(sourceFilePath)?), id, &options)
+    }
+
+    /// 从输入流替换资源
+    ///
+    /// 先将新资源写入资源数据末尾，成功后再移除原资源并前移其后的数据；读取输入流失败时载体文件保持不变
+    /// # 参数
+    /// 1. 资源数据输入流(如标准输入)
+    /// 2. 资源ID(必须已存在)
+    /// 3. 增加资源选项(未指定资源文件名时使用资源ID)
+    pub fn update_from<R: Read>(&mut self, source: &mut R, id: &str, options: &AddOptions) -> Result<(), Box<dyn Error>> {
+        let mut entries = self.entries()?.to_vec();
+        let position = self.position(id)?;
+        let table = self.certificateTable(options.signature)?;
+        let mut targetFile = OpenOptions::new().read(true).write(true).open(&self.path)?;
+
+        self.entries = None;
+        targetFile.set_len(self.dataEnd)?;
+        targetFile.seek(SeekFrom::Start(self.dataEnd))?;
+        match writeResourceFrom(&mut targetFile, source, id, options.name.as_deref().unwrap_or(id), options) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => {
+                self.restore(&mut targetFile, entries, table, options.keepChecksum)?;
+                return Err(e);
+            }
+        }
+        self.cutEntry(&mut targetFile, &mut entries, position)?;
+
+        self.writeIndex(&mut targetFile, entries, table)?;
+        self.finish(&mut targetFile, options.keepChecksum)
+    }
+
+    /// 增加目录下的所有文件(递归)
+    ///
+    /// 资源ID为前缀加以 "/" 分隔的相对路径，资源文件名为相对路径；所有资源一次写入载体文件，只重写一次资源索引
+    /// # 参数
+    /// 1. 资源目录
+    /// 2. 资源ID前缀(可以为空)
+    /// 3. 增加资源选项(资源文件名选项无效)
+    /// # 返回值
+    /// 增加的资源ID
+    pub fn add_directory(&mut self, directory: &Path, prefix: &str, options: &AddOptions) -> Result<Vec<String>, Box<dyn Error>> {
+        let files = listFiles(directory)?;
+        let mut entries = self.entries()?.to_vec();
+        for (_, relative) in &files {
+            let id = format!("{}{}", prefix, relative);
+            checkResourceInfo(&id, relative)?;
+            if !options.replace && entries.iter().any(|entry| entry.head.getId().eq(&id)) {
+                return Err(Box::from(AppenderError::DuplicateId(format!("Resource id \"{}\" already exists, please use another id or replace it", id))));
+            }
+        }
+        let table = self.certificateTable(options.signature)?;
+        let mut targetFile = OpenOptions::new().read(true).write(true).open(&self.path)?;
+
+        // 移除旧的资源索引，先写入所有新资源，全部成功后再移除被替换的资源(失败时原有资源保持不变)
+        self.entries = None;
+        targetFile.set_len(self.dataEnd)?;
+        targetFile.seek(SeekFrom::Start(self.dataEnd))?;
+
+        let mut ids = Vec::with_capacity(files.len());
+        let mut added = Vec::with_capacity(files.len());
+        for (path, relative) in &files {
+            let id = format!("{}{}", prefix, relative);
+            let result = File::open(path).map_err(Box::<dyn Error>::from).and_then(|file| {
+                writeResourceFrom(&mut targetFile, &mut BufReader::with_capacity(BUFFER_SIZE, file), &id, relative, options)
+            });
+            match result {
+                Ok(entry) => added.push(entry),
+                Err(e) => {
+                    self.restore(&mut targetFile, entries, table, options.keepChecksum)?;
+                    return Err(withContext(e, &format!("Failed to add \"{}\"", path.display())));
+                }
+            }
+            ids.push(id);
+        }
+        let mut existing = entries.len();
+        entries.extend(added);
+        for id in &ids {
+            if let Some(position) = entries[..existing].iter().position(|entry| entry.head.getId() == id) {
+                self.cutEntry(&mut targetFile, &mut entries, position)?;
+                existing -= 1;
+            }
+        }
+
+        self.writeIndex(&mut targetFile, entries, table)?;
+        self.finish(&mut targetFile, options.keepChecksum)?;
+        Ok(ids)
+    }
+
+    /// 删除资源(移除资源文件头、资源数据及尾部标识，并将其后的资源前移)
+    pub fn remove(&mut self, id: &str) -> Result<(), Box<dyn Error>> {
+        let mut entries = self.entries()?.to_vec();
+        let position = self.position(id)?;
+        // 删除资源不会使签名失效，证书表无法调整时直接删除
+        let table = self.certificateTable(SignatureMode::Embed).unwrap_or(None);
+        let mut targetFile = OpenOptions::new().read(true).write(true).open(&self.path)?;
+
+        self.entries = None;
+        self.cutEntry(&mut targetFile, &mut entries, position)?;
+        self.writeIndex(&mut targetFile, entries, table)?;
+        self.finish(&mut targetFile, false)
+    }
+
+    /// 将旧版本的资源迁移为当前版本(原地重写资源文件头，资源数据不变)
+    ///
+    /// 当前格式的文件头比旧格式短，其后的资源随之前移，有填充的资源移动后仍保持对齐；当前版本及新版本的资源文件头保持不变。
+    /// 第一个资源紧跟映像(或证书表)时文件头位置不变；其前还有其他数据(无法与对齐填充区分)时数据位置不变。
+    /// 1.0.0 版本的压缩资源解压一次，记录原始大小
+    /// # 返回值
+    /// 迁移的资源ID，没有旧版本资源时不修改文件
+    pub fn migrate(&mut self) -> Result<Vec<String>, Box<dyn Error>> {
+        let mut entries = self.entries()?.to_vec();
+        let mut order: Vec<usize> = (0..entries.len()).collect();
+        order.sort_by_key(|&i| entries[i].offset);
+
+        // 先计算所有资源的新位置(新文件头, 新位置, 填充长度)，资源只能前移，否则会覆盖尚未移动的数据
+        let mut migrated = Vec::new();
+        let mut plan = Vec::with_capacity(order.len());
+        let mut writeOffset = order.first().map_or(0, |&i| entries[i].offset);
+        let mut previousEnd = writeOffset;
+        let overlayStart = self.overlayStart(writeOffset)?;
+        for &i in &order {
+            let entry = &entries[i];
+            let head = if versionCompatibility(entry.head.getVersion())? == VersionCompatibility::Older {
+                migrated.push(entry.head.getId().to_string());
+                let mut head = entry.head.upgraded();
+                if !entry.head.isSizeKnown() {
+                    head.setSize(readResource(&mut self.file, entry, &mut io::sink(), self.secret.as_ref())?);
+                }
+                Some(head)
+            } else {
+                None
+            };
+            let head = head.map(|head| head.to_bytes().map(|bytes| (head, bytes))).transpose()?;
+            let headLen = head.as_ref().map_or(entry.headLen, |(_, bytes)| bytes.len() as u64);
+            let padding = if plan.is_empty() {
+                // 第一个资源之前的对齐填充无法与其他附加数据区分，其前有数据时数据位置保持不变
+                if entry.offset > overlayStart { entry.dataOffset() - headLen.min(entry.headLen) - writeOffset } else { 0 }
+            } else {
+                let alignment = if entry.offset > previousEnd { dataAlignment(entry.dataOffset()) } else { 1 };
+                alignmentPadding(writeOffset + headLen, alignment)?
+            };
+            let offset = writeOffset + padding;
+            if offset + headLen > entry.dataOffset() {
+                return Err(Box::from(AppenderError::Corrupt(format!("Resource \"{}\" overlaps the previous resource and cannot be migrated in place", entry.head.getId()))));
+            }
+            previousEnd = entry.nextOffset();
+            writeOffset = offset + headLen + (entry.nextOffset() - entry.dataOffset());
+            plan.push((i, head, offset, padding));
+        }
+        if migrated.is_empty() {
+            return Ok(migrated);
+        }
+
+        // 迁移不会使签名失效，证书表无法调整时直接修改
+        let table = self.certificateTable(SignatureMode::Embed).unwrap_or(None);
+        let mut targetFile = OpenOptions::new().read(true).write(true).open(&self.path)?;
+        self.entries = None;
+        for (i, head, offset, padding) in plan {
+            let entry = &mut entries[i];
+            targetFile.seek(SeekFrom::Start(offset - padding))?;
+            targetFile.write_all(&vec![0u8; padding as usize])?;
+            match head {
+                Some((head, bytes)) => {
+                    targetFile.write_all(&bytes)?;
+                    moveData(&mut targetFile, entry.dataOffset(), offset + bytes.len() as u64, entry.nextOffset() - entry.dataOffset())?;
+                    entry.headLen = bytes.len() as u64;
+                    entry.head = head;
+                }
+                None => moveData(&mut targetFile, entry.offset, offset, entry.nextOffset() - entry.offset)?,
+            }
+            entry.offset = offset;
+        }
+        targetFile.set_len(writeOffset)?;
+        targetFile.seek(SeekFrom::Start(writeOffset))?;
+        self.writeIndex(&mut targetFile, entries, table)?;
+        self.finish(&mut targetFile, false)?;
+        Ok(migrated)
+    }
+
+    /// 获取资源条目的序号
+    fn position(&mut self, id: &str) -> Result<usize, Box<dyn Error>> {
+        self.entries()?.iter().position(|entry| entry.head.getId().eq(id)).ok_or_else(|| Box::from(AppenderError::NotFound("Resource not found".to_string())))
+    }
+
+    /// 从文件中移除资源条目，将其后的数据前移并截断文件
+    ///
+    /// 其后的资源前有填充(增加时指定了对齐)时，按其数据起始位置的对齐长度重新填充，移动后仍保持对齐。
+    /// 完成后文件位置位于新的资源数据末尾
+    fn cutEntry(&mut self, targetFile: &mut File, entries: &mut Vec<ResourceEntry>, position: usize) -> Result<(), Box<dyn Error>> {
+        let removed = entries.remove(position);
+        // 第一个资源之前的数据可能不属于资源(证书表等)，只移除前一个资源之后的填充
+        let mut writeOffset = entries.iter().filter(|entry| entry.offset < removed.offset)
+            .map(|entry| entry.nextOffset()).max().unwrap_or(removed.offset);
+        let mut previousEnd = removed.nextOffset();
+
+        // 按位置顺序将其后的资源逐个前移(不改变索引中的顺序)
+        let mut following: Vec<&mut ResourceEntry> = entries.iter_mut().filter(|entry| entry.offset > removed.offset).collect();
+        following.sort_by_key(|entry| entry.offset);
+        for entry in following {
+            let alignment = if entry.offset > previousEnd { dataAlignment(entry.dataOffset()) } else { 1 };
+            let padding = alignmentPadding(writeOffset + entry.headLen, alignment)?;
+            targetFile.seek(SeekFrom::Start(writeOffset))?;
+            targetFile.write_all(&vec![0u8; padding as usize])?;
+
+            previousEnd = entry.nextOffset();
+            let length = entry.nextOffset() - entry.offset;
+            moveData(targetFile, entry.offset, writeOffset + padding, length)?;
+            entry.offset = writeOffset + padding;
+            writeOffset = entry.nextOffset();
+        }
+
+        self.dataEnd = writeOffset;
+        targetFile.set_len(self.dataEnd)?;
+        targetFile.seek(SeekFrom::Start(self.dataEnd))?;
+        Ok(())
+    }
+
+    /// 检查目标文件的数字签名
+    ///
+    /// Mach-O 的代码签名无法保留，除拒绝模式外直接追加
+    /// # 返回值
+    /// 需要将资源嵌入的证书表，不需要处理数字签名时返回 None
+    fn certificateTable(&mut self, mode: SignatureMode) -> Result<Option<CertificateTable>, Box<dyn Error>> {
+        let info = self.image_info();
+        if matches!(info.format, CarrierFormat::MachO | CarrierFormat::FatMachO) && info.signed && mode == SignatureMode::Refuse {
+            return Err(Box::from(AppenderError::InvalidArgument("The target file has a code signature, modifying it would invalidate the signature".to_string())));
+        }
+        // 无法解析的 PE 文件按普通文件处理
+        let image = match PeImage::parse(&mut self.file) {
+            Ok(Some(image)) if image.isSigned() => image,
+            _ => return Ok(None),
+        };
+        match mode {
+            SignatureMode::Append => Ok(None),
+            SignatureMode::Refuse => Err(Box::from(AppenderError::InvalidArgument("The target file is signed, modifying it would invalidate the signature".to_string()))),
+            SignatureMode::Embed => {
+                let dataStart = self.dataStart()?;
+                match image.certificateTable(&mut self.file)? {
+                    // 证书表必须位于映像之后、资源之前，才能将资源包含在证书表中
+                    Some(table) if table.address >= image.imageEnd && table.address <= dataStart => Ok(Some(table)),
+                    _ => Err(Box::from(AppenderError::InvalidArgument("The certificate table is not followed by the resources, modifying the file would invalidate the signature".to_string()))),
+                }
+            }
+        }
+    }
+
+    /// 在当前位置(资源数据末尾)重新写入资源索引，没有资源时不写入索引
+    ///
+    /// 需要嵌入证书表时，在索引前填充使证书表长度 8 字节对齐，并将证书表延伸到文件末尾
+    fn writeIndex(&mut self, targetFile: &mut File, entries: Vec<ResourceEntry>, table: Option<CertificateTable>) -> Result<(), Box<dyn Error>> {
+        self.dataEnd = targetFile.stream_position()?;
+        let index = ResourceIndex::fromEntries(&entries);
+        let indexLength = if entries.is_empty() { 0 } else { index.length()? };
+        if let Some(table) = &table {
+            let padding = (CERTIFICATE_ALIGNMENT - (self.dataEnd + indexLength - table.address) % CERTIFICATE_ALIGNMENT) % CERTIFICATE_ALIGNMENT;
+            targetFile.write_all(&vec![0u8; padding as usize])?;
+        }
+        if !entries.is_empty() {
+            index.write(targetFile)?;
+        }
+        if let Some(mut table) = table {
+            let end = targetFile.stream_position()?;
+            table.resize(targetFile, end)?;
+        }
+        self.entries = Some(entries);
+        Ok(())
+    }
+
+    /// 写入资源失败时丢弃已写入的数据(截断到资源数据末尾)，并恢复原有资源的索引
+    fn restore(&mut self, targetFile: &mut File, entries: Vec<ResourceEntry>, table: Option<CertificateTable>, keepChecksum: bool) -> Result<(), Box<dyn Error>> {
+        targetFile.set_len(self.dataEnd)?;
+        targetFile.seek(SeekFrom::Start(self.dataEnd))?;
+        self.writeIndex(targetFile, entries, table)?;
+        self.finish(targetFile, keepChecksum)
+    }
+
+    /// 完成修改: 重新计算 PE 文件头中的校验和(不是 PE 文件或设置保留校验和时跳过)
+    fn finish(&mut self, targetFile: &mut File, keepChecksum: bool) -> Result<(), Box<dyn Error>> {
+        if keepChecksum || self.keepChecksum {
+            return Ok(());
+        }
+        if let Ok(Some(image)) = PeImage::parse(targetFile) {
+            image.updateChecksum(targetFile)?;
+        }
+        Ok(())
+    }
+}
+
+/// 推断资源数据的对齐长度(数据起始位置能整除的最大的 2 的幂，不超过最大对齐长度)
+fn dataAlignment(dataOffset: u64) -> u64 {
+    if dataOffset == 0 { MAX_ALIGNMENT } else { std::cmp::min(1 << dataOffset.trailing_zeros(), MAX_ALIGNMENT) }
+}
+
+/// 资源文件名(选项未指定时使用资源文件的文件名)
+fn sourceName(sourceFilePath: &Path, options: &AddOptions) -> String {
+    match &options.name {
+        Some(name) => name.clone(),
+        None => sourceFilePath.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default(),
+    }
+}
+
+/// 检查资源文件名并转换为安全的相对路径(释放到目录时使用)
+fn safeName(entry: &ResourceEntry) -> Result<PathBuf, Box<dyn Error>> {
+    safeRelativePath(entry.head.getName()).map_err(|e| {
+        Box::from(AppenderError::InvalidHeader(format!("The file name \"{}\" of resource \"{}\" was rejected: {}", entry.head.getName(), entry.head.getId(), e)))
+    })
+}
+
+/// 在文件名后添加序号(扩展名之前)
+fn renamedPath(path: &Path, index: u32) -> PathBuf {
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+    let name = match path.extension() {
+        Some(extension) => format!("{} ({}).{}", stem, index, extension.to_string_lossy()),
+        None => format!("{} ({})", stem, index),
+    };
+    path.with_file_name(name)
+}
+
+/// 释放资源时使用的临时文件路径(与输出文件位于同一目录，重命名时不跨越文件系统)
+fn temporaryPath(path: &Path) -> PathBuf {
+    let name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    path.with_file_name(format!(".{}.{}.tmp", name, std::process::id()))
+}
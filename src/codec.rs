@@ -0,0 +1,178 @@
+use std::error::Error;
+use std::io::{Read, Write, copy};
+use crate::core::CompressMode;
+use crate::util::compressionStream;
+use crate::error::AppenderError;
+
+/// 编解码器(压缩算法)
+pub trait Codec {
+    /// 压缩数据流
+    /// # 参数
+    /// 1. 输入流
+    /// 2. 输出流
+    /// 3. 压缩等级
+    /// # 返回值
+    /// 读取的原始数据字节数
+    fn compress(&self, input: &mut dyn Read, output: &mut dyn Write, compressionGrade: u32) -> Result<u64, Box<dyn Error>>;
+
+    /// 创建解压读取器
+    fn decoder<'a>(&self, input: Box<dyn Read + 'a>) -> Result<Box<dyn Read + 'a>, Box<dyn Error>>;
+}
+
+/// 无压缩
+struct Store;
+
+impl Codec for Store {
+    fn compress(&self, input: &mut dyn Read, output: &mut dyn Write, _compressionGrade: u32) -> Result<u64, Box<dyn Error>> {
+        Ok(copy(input, output)?)
+    }
+
+    fn decoder<'a>(&self, input: Box<dyn Read + 'a>) -> Result<Box<dyn Read + 'a>, Box<dyn Error>> {
+        Ok(input)
+    }
+}
+
+/// gzip 压缩
+struct Gzip;
+
+impl Codec for Gzip {
+    fn compress(&self, input: &mut dyn Read, output: &mut dyn Write, compressionGrade: u32) -> Result<u64, Box<dyn Error>> {
+        compressionStream(input, output, compressionGrade)
+    }
+
+    fn decoder<'a>(&self, input: Box<dyn Read + 'a>) -> Result<Box<dyn Read + 'a>, Box<dyn Error>> {
+        Ok(Box::new(flate2::read::GzDecoder::new(input)))
+    }
+}
+
+/// zstd 压缩(解压速度快)
+#[cfg(feature = "zstd")]
+struct Zstd;
+
+#[cfg(feature = "zstd")]
+impl Codec for Zstd {
+    fn compress(&self, input: &mut dyn Read, output: &mut dyn Write, compressionGrade: u32) -> Result<u64, Box<dyn Error>> {
+        let mut encoder = zstd::stream::write::Encoder::new(output, compressionGrade as i32)?;
+        let size = copy(input, &mut encoder)?;
+        encoder.finish()?;
+        Ok(size)
+    }
+
+    fn decoder<'a>(&self, input: Box<dyn Read + 'a>) -> Result<Box<dyn Read + 'a>, Box<dyn Error>> {
+        Ok(Box::new(zstd::stream::read::Decoder::new(input)?))
+    }
+}
+
+/// xz 压缩(压缩率高)
+#[cfg(feature = "xz")]
+struct Xz;
+
+#[cfg(feature = "xz")]
+impl Codec for Xz {
+    fn compress(&self, input: &mut dyn Read, output: &mut dyn Write, compressionGrade: u32) -> Result<u64, Box<dyn Error>> {
+        let mut encoder = xz2::write::XzEncoder::new(output, compressionGrade);
+        let size = copy(input, &mut encoder)?;
+        encoder.finish()?;
+        Ok(size)
+    }
+
+    fn decoder<'a>(&self, input: Box<dyn Read + 'a>) -> Result<Box<dyn Read + 'a>, Box<dyn Error>> {
+        Ok(Box::new(xz2::read::XzDecoder::new(input)))
+    }
+}
+
+/// lz4 压缩(压缩、解压速度最快，忽略压缩等级)
+#[cfg(feature = "lz4")]
+struct Lz4;
+
+#[cfg(feature = "lz4")]
+impl Codec for Lz4 {
+    fn compress(&self, input: &mut dyn Read, output: &mut dyn Write, _compressionGrade: u32) -> Result<u64, Box<dyn Error>> {
+        let mut encoder = lz4_flex::frame::FrameEncoder::new(output);
+        let size = copy(input, &mut encoder)?;
+        encoder.finish()?;
+        Ok(size)
+    }
+
+    fn decoder<'a>(&self, input: Box<dyn Read + 'a>) -> Result<Box<dyn Read + 'a>, Box<dyn Error>> {
+        Ok(Box::new(lz4_flex::frame::FrameDecoder::new(input)))
+    }
+}
+
+impl CompressMode {
+    /// 所有压缩模式名称
+    pub const NAMES: [&'static str; 5] = ["none", "gzip", "zstd", "xz", "lz4"];
+
+    /// 获取压缩模式名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompressMode::None => "none",
+            CompressMode::Compress => "gzip",
+            CompressMode::Zstd => "zstd",
+            CompressMode::Xz => "xz",
+            CompressMode::Lz4 => "lz4",
+        }
+    }
+
+    /// 由压缩模式名称解析
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "none" => Some(CompressMode::None),
+            "gzip" => Some(CompressMode::Compress),
+            "zstd" => Some(CompressMode::Zstd),
+            "xz" => Some(CompressMode::Xz),
+            "lz4" => Some(CompressMode::Lz4),
+            _ => None,
+        }
+    }
+
+    /// 获取压缩模式编号(v2 资源文件头中保存的编号，与序列化的变体序号相同)
+    pub fn id(&self) -> u8 {
+        match self {
+            CompressMode::None => 0,
+            CompressMode::Compress => 1,
+            CompressMode::Zstd => 2,
+            CompressMode::Xz => 3,
+            CompressMode::Lz4 => 4,
+        }
+    }
+
+    /// 由压缩模式编号解析
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(CompressMode::None),
+            1 => Some(CompressMode::Compress),
+            2 => Some(CompressMode::Zstd),
+            3 => Some(CompressMode::Xz),
+            4 => Some(CompressMode::Lz4),
+            _ => None,
+        }
+    }
+
+    /// 压缩等级范围(不支持压缩等级时返回 None)
+    pub fn gradeRange(&self) -> Option<(u32, u32)> {
+        match self {
+            CompressMode::None | CompressMode::Lz4 => None,
+            CompressMode::Compress | CompressMode::Xz => Some((0, 9)),
+            CompressMode::Zstd => Some((1, 22)),
+        }
+    }
+
+    /// 获取编解码器
+    /// # 返回值
+    /// 编译时未启用对应功能(feature)时返回错误
+    pub fn codec(&self) -> Result<Box<dyn Codec>, Box<dyn Error>> {
+        match self {
+            CompressMode::None => Ok(Box::new(Store)),
+            CompressMode::Compress => Ok(Box::new(Gzip)),
+            #[cfg(feature = "zstd")]
+            CompressMode::Zstd => Ok(Box::new(Zstd)),
+            #[cfg(feature = "xz")]
+            CompressMode::Xz => Ok(Box::new(Xz)),
+            #[cfg(feature = "lz4")]
+            CompressMode::Lz4 => Ok(Box::new(Lz4)),
+            #[allow(unreachable_patterns)]
+            _ => Err(Box::from(AppenderError::InvalidArgument(format!("The compression mode \"{}\" is not supported by this build, please enable the \"{}\" feature", self.name(), self.name())))),
+        }
+    }
+}
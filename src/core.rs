@@ -1,13 +1,17 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::error::Error;
-use std::fs::{OpenOptions, File};
-use std::io::{Write, Read, SeekFrom, Seek};
-use std::convert::TryFrom;
+use std::fs::File;
+use std::io::{Write, Read, SeekFrom, Seek, BufReader, copy};
 use std::fs;
 use std::cmp::Ordering;
 use serde::{Serialize, Deserialize};
-use memchr::memmem;
-use crate::util::{compressionFile, decompressFile};
+use crate::carrier::Carrier;
+use crate::util::{DigestReader, DigestWriter, readUInt};
+use crate::crypto::{self, Cipher, Secret, newParameters, encrypt, decrypt};
+use crate::pe::SignatureMode;
+use crate::format::ImageInfo;
+use crate::scanner::ResourceScanner;
+use crate::error::AppenderError;
 
 
 /// 缓冲区大小（512KB）
@@ -16,86 +20,467 @@ pub const BUFFER_SIZE: usize = 1024 * 512;
 /// 资源最大大小（1024GB）
 pub const MAX_LENGTH_SIZE: u64 = 1024 * 1024 * 1024 * 1024;
 
-/// 最大id长度
-pub const MAX_ID_LENGTH: usize = 64;
+/// 最大id长度(UTF-8 编码的字节数)
+pub const MAX_ID_LENGTH: usize = 255;
 
-/// 最大文件名长度
-pub const MAX_NAME_LENGTH: usize = 255;
+/// 最大文件名长度(UTF-8 编码的字节数，文件名可以包含相对路径)
+pub const MAX_NAME_LENGTH: usize = 1024;
 
-/// 压缩模式
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+/// 当前资源版本(与 v2 文件头的格式版本对应)
+pub const RESOURCE_VERSION: &str = "2.0.0";
+
+/// v2 资源文件头格式版本(高字节为主版本，低字节为次版本)
+pub const FORMAT_VERSION: u16 = 0x0200;
+
+/// v2 资源文件头标识
+pub const HEAD_MAGIC: [u8; 8] = [0x89, b'O', b'D', b'A', b'T', 0x0D, 0x0A, 0x1A];
+
+/// v1 资源文件头标识(bincode 序列化，标识前为 8 字节的标识长度)
+pub const HEAD_MAGIC_V1: [u8; 16] = [0x89, b'O', b'v', b'e', b'r', b'l', b'a', b'y', b'D', b'a', b't', b'a', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// v2 资源文件头固定部分长度
+const HEAD_FIXED_LENGTH: usize = 40;
+
+/// 各主版本文件头共同的起始部分长度(标识、格式版本、文件头长度)
+const HEAD_STABLE_LENGTH: usize = 12;
+
+/// v2 资源文件头标志: 包含原始资源摘要
+const FLAG_DIGEST: u16 = 1;
+
+/// ID、文件名不再填充的资源版本(之前的版本 ID、文件名居中填充空格到 64、255 个字符)
+const UNPADDED_VERSION: &str = "1.3.0";
+
+/// 资源摘要长度(SHA-256)
+pub const DIGEST_LENGTH: usize = 32;
+
+/// 资源数据最大对齐长度(64KB，覆盖常见的内存页大小)
+pub const MAX_ALIGNMENT: u64 = 1024 * 64;
+
+/// 压缩模式(编解码器，见 [`crate::codec`])
+///
+/// 序列化时使用变体序号，新增的压缩模式只能追加在末尾
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
 pub enum CompressMode {
     /// 无压缩
+    #[default]
     None,
-    /// 有压缩
+    /// gzip 压缩(沿用 1.0.0 的命名)
+    #[serde(rename = "gzip")]
     Compress,
+    /// zstd 压缩
+    Zstd,
+    /// xz 压缩
+    Xz,
+    /// lz4 压缩
+    Lz4,
+}
+
+/// 资源文件头最大长度(读取文件头时的缓冲区大小)
+pub const MAX_HEAD_LENGTH: u64 = 4096;
+
+/// 增加资源选项
+#[derive(Debug, Clone, Default)]
+pub struct AddOptions {
+    /// 压缩模式
+    pub compressMode: CompressMode,
+    /// 压缩等级
+    pub compressionGrade: u32,
+    /// 替换已存在的同ID资源
+    pub replace: bool,
+    /// 加密密钥来源(不加密时为 None)
+    pub secret: Option<Secret>,
+    /// 目标文件带有数字签名时的处理方式
+    pub signature: SignatureMode,
+    /// 保留 PE 文件头中的校验和(默认重新计算)
+    pub keepChecksum: bool,
+    /// 资源文件名(默认使用资源文件的文件名，从输入流增加时默认使用资源ID)
+    pub name: Option<String>,
+    /// 资源数据在文件中的对齐长度(2 的幂，0 或 1 表示不对齐)，在资源文件头之前填充
+    pub alignment: u64,
+}
+
+impl AddOptions {
+    /// 由压缩等级创建选项(有压缩等级时使用 gzip 压缩)
+    pub fn compression(compressionGrade: Option<u32>) -> Self {
+        match compressionGrade {
+            Some(grade) => AddOptions { compressMode: CompressMode::Compress, compressionGrade: grade, ..Default::default() },
+            None => AddOptions::default(),
+        }
+    }
 }
 
 /// 资源文件头
-#[derive(Serialize, Deserialize, Debug)]
+///
+/// 写入时使用 v2 二进制格式(小端序):
+/// ```text
+/// | 标识(8) | 格式版本 u16 | 文件头长度 u16 | 标志 u16 | 压缩模式 u8 | 加密算法 u8 | 资源长度 u64 | 资源大小 u64 |
+/// | ID长度 u16 | 文件名长度 u16 | 盐长度 u8 | 随机数长度 u8 | 保留 u16 |
+/// | ID | 文件名 | 摘要(32，有摘要标志时) | 盐 | 随机数 | CRC32 u32 |
+/// ```
+/// 文件头长度包含 CRC32，CRC32 校验之前的所有字节；读取时兼容 v1(bincode 序列化，1.0.0 - 1.3.0)的文件头
+#[derive(Debug, Clone)]
 pub struct ResourceHead {
-    /// 文件头魔数
-    Head: Vec<u8>,
-    /// 资源版本(不应与程序版本号绑定)
+    /// 资源版本(v1 为文件头中的版本字符串，v2 由格式版本转换，如 "2.0.0")
     Version: String,
-    /// 资源ID
+    /// 资源ID(1.3.0 起原样保存，之前的版本读取时去除填充的空格)
     Id: String,
-    /// 资源文件名
+    /// 资源文件名(1.3.0 起原样保存，之前的版本读取时去除填充的空格)
     Name: String,
     /// 资源长度
-    Length: String,
+    Length: u64,
     /// 资源大小
-    Size: String,
+    Size: u64,
     /// 压缩模式
     Compress: CompressMode,
+    /// 原始资源的 SHA-256 摘要(1.1.0 起)
+    Digest: Vec<u8>,
+    /// 加密算法(1.2.0 起)
+    Cipher: Cipher,
+    /// 派生密钥使用的盐(使用密钥文件时为空)
+    Salt: Vec<u8>,
+    /// 加密随机数前缀
+    Nonce: Vec<u8>,
 }
 
-impl ResourceHead {
-    pub(crate) fn default() -> Self {
-        ResourceHead::new("", 0, 0, "", CompressMode::None)
-    }
+/// v1 资源文件头的公共前缀(各版本相同，用于判断资源版本)
+#[derive(Deserialize)]
+struct ResourceHeadPrefix {
+    Head: Vec<u8>,
+    Version: String,
+}
+
+/// 1.0.0 版本的资源文件头(没有摘要)
+#[derive(Serialize, Deserialize)]
+struct ResourceHeadV1_0 {
+    Head: Vec<u8>,
+    Version: String,
+    Id: String,
+    Name: String,
+    Length: String,
+    Size: String,
+    Compress: CompressMode,
+}
+
+/// 1.1.0 版本的资源文件头(没有加密信息)
+#[derive(Serialize, Deserialize)]
+struct ResourceHeadV1_1 {
+    Head: Vec<u8>,
+    Version: String,
+    Id: String,
+    Name: String,
+    Length: String,
+    Size: String,
+    Compress: CompressMode,
+    Digest: Vec<u8>,
+}
+
+/// 1.2.0、1.3.0 版本的资源文件头(1.3.0 起 ID、文件名不再填充)
+#[derive(Serialize, Deserialize)]
+struct ResourceHeadV1_2 {
+    Head: Vec<u8>,
+    Version: String,
+    Id: String,
+    Name: String,
+    Length: String,
+    Size: String,
+    Compress: CompressMode,
+    Digest: Vec<u8>,
+    Cipher: Cipher,
+    Salt: Vec<u8>,
+    Nonce: Vec<u8>,
+}
 
+impl ResourceHead {
     pub fn new(id: &str, length: u64, size: u64, name: &str, CompressMode: CompressMode) -> Self {
-        // 注意: 如有中文填充需要使用 指定字符串长度 - 字符串.len() + 字符串.chars().count()
         ResourceHead {
-            Head: [
-                // 非 ASCII 字符以防止来自文本文件的干扰
-                vec![0x89].as_slice(),
-                b"OverlayData".to_vec().as_slice(),
-                vec![0x0d, 0x0a, 0x1a, 0x0a].as_slice()
-            ].concat(),
-            Id: format!("{:^length$}", id, length = MAX_ID_LENGTH - id.len() + id.chars().count()),
-            Name: format!("{:^length$}", name, length = MAX_NAME_LENGTH - name.len() + name.chars().count()),
-            Length: format!("{:0>length$}", length, length = MAX_LENGTH_SIZE.to_string().len()),
-            Size: format!("{:0>length$}", size, length = MAX_LENGTH_SIZE.to_string().len()),
-            Version: "1.0.0".to_string(),
+            Version: RESOURCE_VERSION.to_string(),
+            Id: id.to_string(),
+            Name: name.to_string(),
+            Length: length,
+            Size: size,
             Compress: CompressMode,
+            Digest: vec![0; DIGEST_LENGTH],
+            Cipher: Cipher::None,
+            Salt: Vec::new(),
+            Nonce: Vec::new(),
         }
     }
 
-    /// 获取文件头长度
+    /// 设置加密信息
+    pub fn setCipher(&mut self, cipher: Cipher, salt: &[u8], nonce: &[u8]) {
+        self.Cipher = cipher;
+        self.Salt = salt.to_vec();
+        self.Nonce = nonce.to_vec();
+    }
+
+    /// 设置原始资源的摘要
+    pub fn setDigest(&mut self, digest: &[u8]) {
+        self.Digest = digest.to_vec();
+    }
+
+    /// 获取文件头长度(v2 格式)
     pub fn getLen(&self) -> usize {
         self.to_bytes().unwrap().len()
     }
 
-    /// 获取文件头魔数（标识）
-    pub fn getHead(&self) -> &Vec<u8> {
-        &self.Head
+    /// 获取资源版本
+    pub fn getVersion(&self) -> &str {
+        &self.Version
+    }
+
+    /// 获取资源ID
+    pub fn getId(&self) -> &str {
+        &self.Id
     }
 
-    /// 转换为字节
-    pub fn to_bytes(&self) -> Result<Vec<u8>, Box<bincode::ErrorKind>> {
-        bincode::serialize(&self)
+    /// 获取资源文件名
+    pub fn getName(&self) -> &str {
+        &self.Name
+    }
+
+    /// 获取资源长度(写入载体的字节数)
+    pub fn getLength(&self) -> u64 {
+        self.Length
+    }
+
+    /// 获取资源大小(原始文件的字节数，见 [`ResourceHead::isSizeKnown`])
+    pub fn getSize(&self) -> u64 {
+        self.Size
+    }
+
+    /// 原始资源大小是否已知
+    ///
+    /// 1.0.0 版本写入压缩资源时，资源大小记录的是压缩后的长度，原始大小只能通过解压得到
+    pub fn isSizeKnown(&self) -> bool {
+        !(self.Version == "1.0.0" && self.Compress != CompressMode::None)
+    }
+
+    /// 设置原始资源大小(解压得到未知的原始大小后使用)
+    pub fn setSize(&mut self, size: u64) {
+        self.Size = size;
+    }
+
+    /// 获取压缩模式
+    pub fn getCompressMode(&self) -> CompressMode {
+        self.Compress
+    }
+
+    /// 获取原始资源的摘要(1.0.0 版本资源没有摘要，返回空)
+    pub fn getDigest(&self) -> &[u8] {
+        &self.Digest
+    }
+
+    /// 获取加密算法
+    pub fn getCipher(&self) -> Cipher {
+        self.Cipher
+    }
+
+    /// 获取派生密钥的盐(未加密或使用密钥文件时为空)
+    pub fn getSalt(&self) -> &[u8] {
+        &self.Salt
+    }
+
+    /// 转换为当前版本的资源文件头(资源数据的格式不变，用于迁移旧版本资源)
+    pub fn upgraded(&self) -> Self {
+        ResourceHead { Version: RESOURCE_VERSION.to_string(), ..self.clone() }
+    }
+
+    /// 转换为字节(v2 格式)
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        if self.Id.len() > u16::MAX as usize || self.Name.len() > u16::MAX as usize {
+            return Err(Box::from(AppenderError::InvalidArgument("The resource id or file name is too long".to_string())));
+        }
+        if !self.Digest.is_empty() && self.Digest.len() != DIGEST_LENGTH {
+            return Err(Box::from(AppenderError::InvalidArgument(format!("The resource digest must be {} bytes", DIGEST_LENGTH))));
+        }
+        if self.Salt.len() > u8::MAX as usize || self.Nonce.len() > u8::MAX as usize {
+            return Err(Box::from(AppenderError::InvalidArgument("The encryption parameters are too long".to_string())));
+        }
+        let headLen = HEAD_FIXED_LENGTH + self.Id.len() + self.Name.len() + self.Digest.len() + self.Salt.len() + self.Nonce.len() + 4;
+        if headLen > u16::MAX as usize {
+            return Err(Box::from(AppenderError::InvalidArgument("The resource head is too long".to_string())));
+        }
+        let flags = if self.Digest.is_empty() { 0 } else { FLAG_DIGEST };
+
+        let mut data = Vec::with_capacity(headLen);
+        data.extend_from_slice(&HEAD_MAGIC);
+        data.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+        data.extend_from_slice(&(headLen as u16).to_le_bytes());
+        data.extend_from_slice(&flags.to_le_bytes());
+        data.push(self.Compress.id());
+        data.push(self.Cipher.id());
+        data.extend_from_slice(&self.Length.to_le_bytes());
+        data.extend_from_slice(&self.Size.to_le_bytes());
+        data.extend_from_slice(&(self.Id.len() as u16).to_le_bytes());
+        data.extend_from_slice(&(self.Name.len() as u16).to_le_bytes());
+        data.push(self.Salt.len() as u8);
+        data.push(self.Nonce.len() as u8);
+        data.extend_from_slice(&0u16.to_le_bytes());
+        for field in [self.Id.as_bytes(), self.Name.as_bytes(), &self.Digest, &self.Salt, &self.Nonce] {
+            data.extend_from_slice(field);
+        }
+        data.extend_from_slice(&crc32fast::hash(&data).to_le_bytes());
+        Ok(data)
     }
 
     /// 将字节解析为当前数据
-    pub fn from(data: &[u8]) -> Result<Self, Box<bincode::ErrorKind>> {
-        bincode::deserialize(data)
+    pub fn from(data: &[u8]) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::parse(data)?.0)
+    }
+
+    /// 将字节解析为当前数据(兼容 v1 资源文件头)
+    /// # 返回值
+    /// (资源文件头, 文件头在数据中的长度)
+    pub fn parse(data: &[u8]) -> Result<(Self, usize), Box<dyn Error>> {
+        if data.starts_with(&HEAD_MAGIC) {
+            return Self::parseV2(data);
+        }
+        let invalid = |e: bincode::Error| Box::<dyn Error>::from(AppenderError::InvalidHeader(format!("Invalid resource head: {}", e)));
+        let prefix: ResourceHeadPrefix = bincode::deserialize(data).map_err(invalid)?;
+        if prefix.Head != HEAD_MAGIC_V1 {
+            return Err(Box::from(AppenderError::InvalidHeader("Invalid resource head".to_string())));
+        }
+        let (head, headLen) = match prefix.Version.as_str() {
+            "1.0.0" => {
+                let head: ResourceHeadV1_0 = bincode::deserialize(data).map_err(invalid)?;
+                let headLen = bincode::serialized_size(&head)? as usize;
+                (ResourceHeadV1_2 {
+                    Head: head.Head,
+                    Version: head.Version,
+                    Id: head.Id,
+                    Name: head.Name,
+                    Length: head.Length,
+                    Size: head.Size,
+                    Compress: head.Compress,
+                    Digest: Vec::new(),
+                    Cipher: Cipher::None,
+                    Salt: Vec::new(),
+                    Nonce: Vec::new(),
+                }, headLen)
+            }
+            "1.1.0" => {
+                let head: ResourceHeadV1_1 = bincode::deserialize(data).map_err(invalid)?;
+                let headLen = bincode::serialized_size(&head)? as usize;
+                (ResourceHeadV1_2 {
+                    Head: head.Head,
+                    Version: head.Version,
+                    Id: head.Id,
+                    Name: head.Name,
+                    Length: head.Length,
+                    Size: head.Size,
+                    Compress: head.Compress,
+                    Digest: head.Digest,
+                    Cipher: Cipher::None,
+                    Salt: Vec::new(),
+                    Nonce: Vec::new(),
+                }, headLen)
+            }
+            _ => {
+                let head: ResourceHeadV1_2 = bincode::deserialize(data).map_err(invalid)?;
+                let headLen = bincode::serialized_size(&head)? as usize;
+                (head, headLen)
+            }
+        };
+
+        // 旧版本的 ID、文件名填充了空格，长度及大小为补零的十进制字符串
+        let padded = compareVersion(&head.Version, UNPADDED_VERSION)?.is_lt();
+        let number = |value: &str| value.trim().parse::<u64>().map_err(|_| Box::<dyn Error>::from(AppenderError::InvalidHeader(format!("Invalid number \"{}\" in the resource head", value.trim()))));
+        Ok((ResourceHead {
+            Length: number(&head.Length)?,
+            Size: number(&head.Size)?,
+            Id: if padded { head.Id.trim().to_string() } else { head.Id },
+            Name: if padded { head.Name.trim().to_string() } else { head.Name },
+            Version: head.Version,
+            Compress: head.Compress,
+            Digest: head.Digest,
+            Cipher: head.Cipher,
+            Salt: head.Salt,
+            Nonce: head.Nonce,
+        }, headLen))
+    }
+
+    /// 解析 v2 资源文件头
+    fn parseV2(data: &[u8]) -> Result<(Self, usize), Box<dyn Error>> {
+        // 各主版本的标识、格式版本、文件头长度及末尾的 CRC32 保持不变，先校验 CRC 再判断版本，
+        // 资源数据中偶然出现的标识不会被误认为新版本的资源
+        if data.len() < HEAD_STABLE_LENGTH + 4 {
+            return Err(Box::from(AppenderError::InvalidHeader("The resource head is incomplete".to_string())));
+        }
+        let read = |offset: usize, size: usize| readUInt(data, offset, size, false);
+        let headLen = read(10, 2) as usize;
+        if headLen < HEAD_STABLE_LENGTH + 4 || headLen > data.len() {
+            return Err(Box::from(AppenderError::InvalidHeader("The resource head is incomplete".to_string())));
+        }
+        if crc32fast::hash(&data[..headLen - 4]) != read(headLen - 4, 4) as u32 {
+            return Err(Box::from(AppenderError::InvalidHeader("The resource head is corrupted, the CRC does not match".to_string())));
+        }
+        let version = read(8, 2) as u16;
+        if version >> 8 != FORMAT_VERSION >> 8 {
+            return Err(Box::from(AppenderError::VersionMismatch(format!("Resource version does not match, the target resource version is {}.{}.0, the current resource version is {}, please try to upgrade the program version", version >> 8, version & 0xFF, RESOURCE_VERSION))));
+        }
+        if headLen < HEAD_FIXED_LENGTH + 4 {
+            return Err(Box::from(AppenderError::InvalidHeader("The resource head is incomplete".to_string())));
+        }
+
+        // 新的次版本可能在末尾增加字段，只读取已知的字段
+        let flags = read(12, 2) as u16;
+        let lengths = [read(32, 2) as usize, read(34, 2) as usize, if flags & FLAG_DIGEST != 0 { DIGEST_LENGTH } else { 0 }, read(36, 1) as usize, read(37, 1) as usize];
+        if HEAD_FIXED_LENGTH + lengths.iter().sum::<usize>() + 4 > headLen {
+            return Err(Box::from(AppenderError::InvalidHeader("The resource head is incomplete".to_string())));
+        }
+        let mut position = HEAD_FIXED_LENGTH;
+        let mut fields = lengths.iter().map(|length| {
+            position += length;
+            data[position - length..position].to_vec()
+        });
+        let mut text = || String::from_utf8(fields.next().unwrap()).map_err(|_| Box::<dyn Error>::from(AppenderError::InvalidHeader("The resource id or file name is not valid UTF-8".to_string())));
+        let (id, name) = (text()?, text()?);
+        Ok((ResourceHead {
+            Version: format!("{}.{}.0", version >> 8, version & 0xFF),
+            Id: id,
+            Name: name,
+            Length: read(16, 8),
+            Size: read(24, 8),
+            Compress: CompressMode::from_id(data[14]).ok_or_else(|| Box::<dyn Error>::from(AppenderError::InvalidHeader(format!("Unknown compression codec {}", data[14]))))?,
+            Cipher: Cipher::from_id(data[15]).ok_or_else(|| Box::<dyn Error>::from(AppenderError::InvalidHeader(format!("Unknown cipher {}", data[15]))))?,
+            Digest: fields.next().unwrap(),
+            Salt: fields.next().unwrap(),
+            Nonce: fields.next().unwrap(),
+        }, headLen))
     }
 }
 
 /// 资源文件尾(ODEND)
-const END_IDENTIFIER: [u8; 5] = [0x4F, 0x44, 0x45, 0x4E, 0x44];
+pub const END_IDENTIFIER: [u8; 5] = [0x4F, 0x44, 0x45, 0x4E, 0x44];
+
+/// 资源条目(资源文件头及其在载体文件中的位置)
+#[derive(Debug, Clone)]
+pub struct ResourceEntry {
+    /// 资源文件头起始位置
+    pub offset: u64,
+    /// 资源文件头长度
+    pub headLen: u64,
+    /// 资源文件头
+    pub head: ResourceHead,
+}
+
+impl ResourceEntry {
+    /// 资源数据起始位置
+    pub fn dataOffset(&self) -> u64 {
+        self.offset + self.headLen
+    }
+
+    /// 资源尾部标识起始位置
+    pub fn endOffset(&self) -> u64 {
+        self.dataOffset() + self.head.getLength()
+    }
+
+    /// 资源结束位置(含尾部标识)
+    pub fn nextOffset(&self) -> u64 {
+        self.endOffset() + END_IDENTIFIER.len() as u64
+    }
+}
 
 /// 增加资源(Overlay 附加数据)
 /// # 参数
@@ -104,230 +489,389 @@ const END_IDENTIFIER: [u8; 5] = [0x4F, 0x44, 0x45, 0x4E, 0x44];
 /// 3. 资源ID（不可重复）
 /// 4. 输出文件(可选)
 pub fn addResource(targetFilePath: &Path, sourceFilePath: &Path, id: &str, compressionGrade: Option<u32>, outputPath: Option<&Path>) -> Result<(), Box<dyn Error>> {
-    // 打开资源文件
-    let sourceFilePath = if sourceFilePath.is_relative() { targetFilePath.parent().unwrap().join(sourceFilePath) } else { sourceFilePath.to_path_buf() };
-    let mut sourceFile = File::open(&sourceFilePath)?;
-    let sourceName = &sourceFilePath.file_name().unwrap().to_str().unwrap();
+    addResourceWith(targetFilePath, sourceFilePath, id, &AddOptions::compression(compressionGrade), outputPath)
+}
 
-    // 处理压缩资源
-    let tempFilePath = &*sourceFilePath.parent().unwrap().join("temp");
-    if let Some(grage) = compressionGrade {
-        compressionFile(&*sourceFilePath, tempFilePath, grage)?;
-        sourceFile = File::open(&tempFilePath)?;
-    }
-    let sourceLength = sourceFile.metadata()?.len();
+/// 按选项增加资源
+/// # 参数
+/// 1. 目标文件
+/// 2. 资源文件
+/// 3. 资源ID（不可重复，除非选项允许替换）
+/// 4. 增加资源选项
+/// 5. 输出文件(可选)
+pub fn addResourceWith(targetFilePath: &Path, sourceFilePath: &Path, id: &str, options: &AddOptions, outputPath: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let sourceFilePath = resolvePath(targetFilePath, sourceFilePath);
+    let targetFilePath = prepareTarget(targetFilePath, outputPath)?;
+    Carrier::open(&targetFilePath)?.add_with(&sourceFilePath, id, options)
+}
+
+/// 从输入流增加资源
+/// # 参数
+/// 1. 目标文件
+/// 2. 资源数据输入流(如标准输入)
+/// 3. 资源ID（不可重复，除非选项允许替换）
+/// 4. 增加资源选项
+/// 5. 输出文件(可选)
+pub fn addResourceFrom<R: Read>(targetFilePath: &Path, source: &mut R, id: &str, options: &AddOptions, outputPath: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let targetFilePath = prepareTarget(targetFilePath, outputPath)?;
+    Carrier::open(&targetFilePath)?.add_from(source, id, options)
+}
+
+/// 增加目录下的所有文件(递归，资源ID为前缀加相对路径)
+/// # 参数
+/// 1. 目标文件
+/// 2. 资源目录
+/// 3. 资源ID前缀(可以为空)
+/// 4. 增加资源选项
+/// 5. 输出文件(可选)
+/// # 返回值
+/// 增加的资源ID
+pub fn addDirectory(targetFilePath: &Path, directory: &Path, prefix: &str, options: &AddOptions, outputPath: Option<&Path>) -> Result<Vec<String>, Box<dyn Error>> {
+    let directory = resolvePath(targetFilePath, directory);
+    let targetFilePath = prepareTarget(targetFilePath, outputPath)?;
+    Carrier::open(&targetFilePath)?.add_directory(&directory, prefix, options)
+}
+
+/// 替换资源
+/// # 参数
+/// 1. 目标文件
+/// 2. 资源文件
+/// 3. 资源ID（必须已存在）
+/// 4. 输出文件(可选)
+pub fn updateResource(targetFilePath: &Path, sourceFilePath: &Path, id: &str, compressionGrade: Option<u32>, outputPath: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let sourceFilePath = resolvePath(targetFilePath, sourceFilePath);
+    let targetFilePath = prepareTarget(targetFilePath, outputPath)?;
+    Carrier::open(&targetFilePath)?.update(&sourceFilePath, id, compressionGrade)
+}
+
+/// 处理相对路径(相对于目标文件所在目录)
+fn resolvePath(targetFilePath: &Path, path: &Path) -> PathBuf {
+    if path.is_relative() { targetFilePath.parent().unwrap().join(path) } else { path.to_path_buf() }
+}
 
-    //以追加模式打开目标文件
-    let targetFilePath = if let Some(outputPath) = outputPath {
-        // 处理相对路径
-        let outputPath = if outputPath.is_relative() { targetFilePath.parent().unwrap().join(outputPath) } else { outputPath.to_path_buf() };
+/// 准备要写入的目标文件(指定输出文件时复制目标文件)
+fn prepareTarget(targetFilePath: &Path, outputPath: Option<&Path>) -> Result<PathBuf, Box<dyn Error>> {
+    Ok(if let Some(outputPath) = outputPath {
+        let outputPath = resolvePath(targetFilePath, outputPath);
         fs::copy(targetFilePath, &outputPath)?;
         outputPath
-    } else { targetFilePath.to_path_buf() };
-
-    let mut targetFile = OpenOptions::new().append(true).open(targetFilePath)?;
+    } else { targetFilePath.to_path_buf() })
+}
 
-    let compressMode = match compressionGrade.is_some() {
-        true => CompressMode::Compress,
-        false => CompressMode::None,
-    };
+/// 写入资源(写入到目标文件的当前位置)
+/// # 参数
+/// 1. 目标文件
+/// 2. 资源文件
+/// 3. 资源ID
+/// 4. 增加资源选项
+/// # 返回值
+/// 写入的资源条目
+pub fn writeResource(targetFile: &mut File, sourceFilePath: &Path, id: &str, options: &AddOptions) -> Result<ResourceEntry, Box<dyn Error>> {
+    let mut sourceFile = BufReader::with_capacity(BUFFER_SIZE, File::open(sourceFilePath)?);
+    let sourceName = options.name.as_deref().unwrap_or_else(|| sourceFilePath.file_name().unwrap().to_str().unwrap());
+    writeResourceFrom(targetFile, &mut sourceFile, id, sourceName, options)
+}
 
-    // 插入标识头
-    let head = ResourceHead::new(id, sourceLength, sourceLength, sourceName, compressMode).to_bytes()?;
-    if head.len() != ResourceHead::default().to_bytes()?.len() {
-        return Err(Box::try_from("The resource information is not standard, please make sure that there are no Chinese symbols in the information").unwrap());
+/// 从输入流写入资源(写入到目标文件的当前位置)
+/// # 参数
+/// 1. 目标文件
+/// 2. 资源数据输入流
+/// 3. 资源ID
+/// 4. 资源文件名
+/// 5. 增加资源选项
+/// # 返回值
+/// 写入的资源条目
+pub fn writeResourceFrom<R: Read>(targetFile: &mut File, sourceFile: &mut R, id: &str, sourceName: &str, options: &AddOptions) -> Result<ResourceEntry, Box<dyn Error>> {
+    let compressMode = options.compressMode;
+    let codec = compressMode.codec()?;
+    if let Some((minGrade, maxGrade)) = compressMode.gradeRange() {
+        if options.compressionGrade < minGrade || options.compressionGrade > maxGrade {
+            return Err(Box::from(AppenderError::InvalidArgument(format!("The compression grade of {} must be between {} and {}", compressMode.name(), minGrade, maxGrade))));
+        }
     }
-    targetFile.write_all(&*head)?;
 
-    // 缓冲区
-    let mut buffer = [0u8; BUFFER_SIZE];
+    // 加密信息
+    let (cipher, salt, nonce) = match &options.secret {
+        Some(secret) => {
+            let (salt, nonce) = newParameters(secret)?;
+            (Cipher::Aes256Gcm, salt, nonce)
+        }
+        None => (Cipher::None, Vec::new(), Vec::new()),
+    };
 
-    // 循环读取并写入资源文件
-    loop {
-        let nbytes = sourceFile.read(&mut buffer)?;
-        targetFile.write_all(&buffer[..nbytes])?;
-        if nbytes < buffer.len() { break; }
-    }
+    // 插入标识头(资源长度及大小在写入资源数据后回填)
+    let mut head = ResourceHead::new(id, 0, 0, sourceName, compressMode);
+    head.setCipher(cipher, &salt, &nonce);
+    checkResourceInfo(id, sourceName)?;
+    let headBytes = head.to_bytes()?;
+
+    // 在文件头之前填充，使资源数据起始位置对齐
+    let position = targetFile.stream_position()?;
+    let padding = alignmentPadding(position + headBytes.len() as u64, options.alignment)?;
+    targetFile.write_all(&vec![0u8; padding as usize])?;
+    let offset = position + padding;
+    targetFile.write_all(&headBytes)?;
+
+    // 写入资源文件(压缩资源直接压缩、加密写入目标文件，不产生临时文件)，同时计算原始资源摘要
+    let mut sourceFile = DigestReader::new(sourceFile);
+    let sourceSize = match &options.secret {
+        Some(secret) => encrypt(&mut *targetFile, secret, &salt, &nonce, |output| codec.compress(&mut sourceFile, output, options.compressionGrade))?,
+        None => codec.compress(&mut sourceFile, targetFile, options.compressionGrade)?,
+    };
+    let sourceLength = targetFile.stream_position()? - offset - headBytes.len() as u64;
 
     // 插入尾部标识
     targetFile.write_all(&END_IDENTIFIER)?;
+    let nextOffset = targetFile.stream_position()?;
+
+    // 回填资源长度、大小及摘要(定长字段，文件头长度不变)
+    let mut head = ResourceHead::new(id, sourceLength, sourceSize, sourceName, compressMode);
+    head.setCipher(cipher, &salt, &nonce);
+    head.setDigest(&sourceFile.finalize());
+    targetFile.seek(SeekFrom::Start(offset))?;
+    targetFile.write_all(&head.to_bytes()?)?;
+    targetFile.seek(SeekFrom::Start(nextOffset))?;
+
+    Ok(ResourceEntry { offset, headLen: headBytes.len() as u64, head })
+}
 
-    // 清除临时压缩资源
-    if tempFilePath.exists(){
-        fs::remove_file(&tempFilePath)?;
+/// 检查资源ID及文件名的长度(UTF-8 编码的字节数)
+pub fn checkResourceInfo(id: &str, name: &str) -> Result<(), Box<dyn Error>> {
+    if id.len() > MAX_ID_LENGTH {
+        return Err(Box::from(AppenderError::InvalidArgument(format!("The resource id \"{}\" is {} bytes long, at most {} bytes (UTF-8) are allowed", id, id.len(), MAX_ID_LENGTH))));
+    }
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(Box::from(AppenderError::InvalidArgument(format!("The file name \"{}\" of resource \"{}\" is {} bytes long, at most {} bytes (UTF-8) are allowed", name, id, name.len(), MAX_NAME_LENGTH))));
     }
     Ok(())
 }
 
+/// 计算对齐需要的填充长度
+/// # 参数
+/// 1. 当前位置
+/// 2. 对齐长度(2 的幂，0 或 1 表示不对齐)
+pub fn alignmentPadding(position: u64, alignment: u64) -> Result<u64, Box<dyn Error>> {
+    if alignment <= 1 {
+        return Ok(0);
+    }
+    if !alignment.is_power_of_two() || alignment > MAX_ALIGNMENT {
+        return Err(Box::from(AppenderError::InvalidArgument(format!("The alignment must be a power of two no greater than {}", MAX_ALIGNMENT))));
+    }
+    Ok((alignment - position % alignment) % alignment)
+}
+
 /// 释放资源
 /// # 参数
 /// 1. 目标文件
 /// 2. 资源ID
 /// 3. 输出路径
 pub fn exportResource(targetFilePath: &Path, id: &str, outputPath: &Path) -> Result<(), Box<dyn Error>> {
-    let defaultResourceHead = ResourceHead::default();
-
-    // 打开目标文件
-    let mut sourceFile = File::open(targetFilePath)?;
-
-    // 缓冲区
-    let mut buffer = [0u8; BUFFER_SIZE];
-    let mut count = 0;
-
-    loop {
-        count += 1;
-        let nbytes = &sourceFile.read(&mut buffer).unwrap();
+    exportResourceWith(targetFilePath, id, outputPath, None)
+}
 
-        // 如果找到了资源
-        // if let Some(size) = find_subsequence(&buffer, &*defaultResourceHead.getHead()) {
-        if let Some(size) = memmem::Finder::new(&*defaultResourceHead.getHead()).find(&buffer) {
-            // 资源文件头起始位置(我也不知道为什么要减8)
-            let startSize = BUFFER_SIZE * (count - 1) + size - 8;
+/// 释放(加密)资源
+/// # 参数
+/// 1. 目标文件
+/// 2. 资源ID
+/// 3. 输出路径
+/// 4. 加密密钥来源(可选)
+pub fn exportResourceWith(targetFilePath: &Path, id: &str, outputPath: &Path, secret: Option<Secret>) -> Result<(), Box<dyn Error>> {
+    // 写出文件(处理相对路径)
+    let outputPath = resolvePath(targetFilePath, outputPath);
+    let mut carrier = Carrier::open(targetFilePath)?;
+    carrier.set_secret(secret);
+    carrier.export(id, &outputPath)?;
+    Ok(())
+}
 
-            let oldSize = sourceFile.stream_position().unwrap();
+/// 释放ID以指定前缀开头的所有资源(重建目录结构)
+/// # 参数
+/// 1. 目标文件
+/// 2. 资源ID前缀(为空时释放所有资源)
+/// 3. 输出目录
+/// 4. 加密密钥来源(可选)
+/// # 返回值
+/// 实际输出的文件路径
+pub fn exportDirectory(targetFilePath: &Path, prefix: &str, outputPath: &Path, secret: Option<Secret>) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let outputPath = resolvePath(targetFilePath, outputPath);
+    let mut carrier = Carrier::open(targetFilePath)?;
+    carrier.set_secret(secret);
+    carrier.export_directory(prefix, &outputPath)
+}
 
-            // 偏移读取配置
-            let mut configBuffer: Vec<u8> = vec![0; defaultResourceHead.getLen()];
-            sourceFile.seek(SeekFrom::Start(startSize as u64))?;
-            sourceFile.read_exact(&mut configBuffer)?;
+/// 释放(加密)资源到输出流
+/// # 参数
+/// 1. 目标文件
+/// 2. 资源ID
+/// 3. 输出流(如标准输出)
+/// 4. 加密密钥来源(可选)
+/// # 返回值
+/// 写出的字节数
+pub fn exportResourceTo<W: Write>(targetFilePath: &Path, id: &str, writer: &mut W, secret: Option<Secret>) -> Result<u64, Box<dyn Error>> {
+    let mut carrier = Carrier::open(targetFilePath)?;
+    carrier.set_secret(secret);
+    carrier.read(id, writer)
+}
 
-            let config = ResourceHead::from(&configBuffer);
-            if let Err(_e) = config {
-                continue;
-            }
-            let config = config.unwrap();
-
-            // println!("在标识头位置: {} 找到配置 {:?}", startSize,config);
-
-            // 判断资源ID
-            if config.Id.trim().eq(id) {
-                // println!("符合当前匹配资源ID: {:?}", config);
-
-                // 判断资源版本号是否支持当前版本
-                let versionOrdering = compareVersion(&config.Version, &defaultResourceHead.Version);
-                if versionOrdering.is_lt() {
-                    return Err(Box::try_from(format!("Resource version does not match, the target resource version is {}, the current resource version is {}, please try to upgrade the program version", &config.Version, &defaultResourceHead.Version)).unwrap());
-                }
-                if versionOrdering.is_gt() {
-                    return Err(Box::try_from(format!("Resource version does not match, the target resource version is {}, the current resource version is {}, please try to lower the program version", &config.Version, &defaultResourceHead.Version)).unwrap());
-                }
-
-                // 判断资源是否完整
-                let resourceLength = config.Length.parse::<usize>().unwrap();
-                sourceFile.seek(SeekFrom::Start((startSize + defaultResourceHead.getLen() as usize + resourceLength) as u64))?;
-                let mut endBuffer: Vec<u8> = vec![0; END_IDENTIFIER.len()];
-                sourceFile.read_exact(&mut *endBuffer)?;
-                if !endBuffer.eq(&END_IDENTIFIER) {
-                    return Err(Box::try_from("The resource to be exported is incomplete").unwrap());
-                }
-
-                // 写出文件(处理相对路径)
-                let outputPath = if outputPath.is_relative() { targetFilePath.parent().unwrap().join(outputPath) } else { outputPath.to_path_buf() };
-                let outputPath = if outputPath.is_dir() { outputPath.join(config.Name.trim()) } else { outputPath };
-                let mut outputFile = File::create(&outputPath)?;
-
-                sourceFile.seek(SeekFrom::Start((startSize + defaultResourceHead.getLen() as usize) as u64))?;
-
-                // 循环读取并写出资源文件
-                loop {
-                    let nbytes = sourceFile.read(&mut buffer)?;
-
-                    // 判断是否找到资源尾
-                    // if let Some(size) = find_subsequence(&buffer, END_IDENTIFIER.as_ref()) {
-                    if let Some(size) = memmem::Finder::new(END_IDENTIFIER.as_ref()).find(&buffer) {
-                        outputFile.write_all(&buffer[..size])?;
-                        break;
-                    } else {
-                        outputFile.write_all(&buffer[..nbytes])?;
-                    }
-                }
-
-                // 处理压缩资源
-                if config.Compress == CompressMode::Compress {
-                    let actualFile = outputPath.parent().unwrap().join("actualFile");
-                    decompressFile(&outputPath, &actualFile)?;
-                    // 替换原资源文件
-                    fs::remove_file(&outputPath)?;
-                    fs::rename(actualFile, &outputPath)?;
-
-                    // buffer = <[u8; 8192]>::try_from(decompress_to_vec(&buffer).unwrap()).unwrap();
-                    // nbytes = buffer.len();
-                    // println!("{:?}", data);
-                }
-
-                // 文件释放完成，检查文件大小
-                if outputFile.metadata()?.len() != config.Size.trim().parse()? {
-                    // 删除释放错误的文件
-                    fs::remove_file(&outputPath)?;
-                    return Err(Box::try_from("The resource to be exported is incomplete").unwrap());
-                }
-
-                // 文件释放成功
-                return Ok(());
-            }
+/// 读取资源数据(解压后写入输出流)
+/// # 参数
+/// 1. 载体文件
+/// 2. 资源条目
+/// 3. 输出流
+/// 4. 加密密钥来源(资源加密时必须提供)
+/// # 返回值
+/// 写出的字节数
+pub fn readResource<W: Write>(sourceFile: &mut File, entry: &ResourceEntry, writer: &mut W, secret: Option<&Secret>) -> Result<u64, Box<dyn Error>> {
+    // 处理加密、压缩资源(直接解压写入输出流，不产生临时文件)
+    let reader = resourceDecoder(sourceFile, entry, secret)?;
+    copyResource(reader, &entry.head, writer)
+}
 
-            sourceFile.seek(SeekFrom::Start(oldSize))?;
-        }
+/// 将解码读取器中的资源数据写入输出流，并检查资源大小及摘要
+/// # 参数
+/// 1. 资源数据的解码读取器(见 [`resourceDecoder`])
+/// 2. 资源文件头
+/// 3. 输出流
+/// # 返回值
+/// 写出的字节数
+pub fn copyResource<R: Read, W: Write>(mut reader: R, config: &ResourceHead, writer: &mut W) -> Result<u64, Box<dyn Error>> {
+    let mut writer = DigestWriter::new(writer);
+    let size = copy(&mut reader, &mut writer).map_err(AppenderError::from_decode)?;
+
+    // 检查资源大小(原始大小未知时只检查摘要)
+    if config.isSizeKnown() && size != config.getSize() {
+        return Err(Box::from(AppenderError::Corrupt("The resource to be exported is incomplete".to_string())));
+    }
 
-        // 如果文件搜寻完毕仍未找到资源信息
-        if nbytes < &buffer.len() {
-            return Err(Box::try_from("Resource not found").unwrap());
-        }
+    // 检查资源摘要(1.0.0 版本资源没有摘要)
+    if !config.getDigest().is_empty() && writer.finalize() != config.getDigest() {
+        return Err(Box::from(AppenderError::Corrupt("The resource to be exported is corrupted, the checksum does not match".to_string())));
     }
+    Ok(size)
 }
 
-/// 寻找资源配置 - 从头至尾
+/// 检查资源版本及尾部标识，并创建资源数据的解码读取器(解密、解压)
 /// # 参数
-/// 1. 目标文件
-/// 2. 回调函数(配置位置, 资源配置)
+/// 1. 载体文件
+/// 2. 资源条目
+/// 3. 加密密钥来源(资源加密时必须提供)
 /// # 返回值
-/// 资源配置 数组
-pub fn _findResourcesConfig(targetFilePath: &Path, callback: fn(startSize: usize, config: &ResourceHead)) -> Result<Vec<ResourceHead>, Box<dyn Error>> {
-    let defaultResourceHead = ResourceHead::default();
+/// 读取原始资源数据的读取器(不检查大小及摘要)
+pub fn resourceDecoder<'a, R: Read + Seek + 'a>(mut sourceFile: R, entry: &ResourceEntry, secret: Option<&Secret>) -> Result<Box<dyn Read + 'a>, Box<dyn Error>> {
+    let config = &entry.head;
 
-    // 打开目标文件
-    let mut sourceFile = File::open(targetFilePath)?;
+    // 判断资源版本号是否支持当前版本(旧版本资源按旧格式读取，主版本相同的新版本忽略新增的信息)
+    if versionCompatibility(&config.Version)? == VersionCompatibility::Incompatible {
+        return Err(Box::from(AppenderError::VersionMismatch(format!("Resource version does not match, the target resource version is {}, the current resource version is {}, please try to upgrade the program version", config.Version, RESOURCE_VERSION))));
+    }
 
-    // 缓冲区
-    let mut buffer = [0u8; BUFFER_SIZE];
-    let mut count = 0;
+    // 判断资源是否完整
+    sourceFile.seek(SeekFrom::Start(entry.endOffset()))?;
+    let mut endBuffer: Vec<u8> = vec![0; END_IDENTIFIER.len()];
+    sourceFile.read_exact(&mut endBuffer)?;
+    if !endBuffer.eq(&END_IDENTIFIER) {
+        return Err(Box::from(AppenderError::Corrupt("The resource to be exported is incomplete".to_string())));
+    }
 
-    let mut configs = Vec::new();
+    // 按资源长度读取资源数据
+    sourceFile.seek(SeekFrom::Start(entry.dataOffset()))?;
+    let data: Box<dyn Read + 'a> = Box::new(sourceFile.take(config.getLength()));
 
-    loop {
-        count += 1;
-        let nbytes = &sourceFile.read(&mut buffer).unwrap();
+    // 处理加密资源
+    let data = match config.Cipher {
+        Cipher::None => data,
+        Cipher::Aes256Gcm => {
+            if !crypto::supported() {
+                return Err(crypto::notSupported());
+            }
+            let secret = secret.ok_or_else(|| AppenderError::InvalidArgument("The resource is encrypted, please provide the password or key file".to_string()))?;
+            decrypt(data, secret, &config.Salt, &config.Nonce)?
+        }
+    };
 
-        // 如果找到了资源
-        // if let Some(size) = find_subsequence(&buffer, &*defaultResourceHead.getHead()) {
-        if let Some(size) = memmem::Finder::new(&*defaultResourceHead.getHead()).find(&buffer) {
-            // 资源文件头起始位置(我也不知道为什么要减8)
-            let startSize = BUFFER_SIZE * (count - 1) + size - 8;
-            let oldSize = sourceFile.stream_position().unwrap();
+    // 处理压缩资源
+    config.Compress.codec()?.decoder(data)
+}
 
-            // 偏移读取配置
-            let mut configBuffer: Vec<u8> = vec![0; defaultResourceHead.getLen()];
-            sourceFile.seek(SeekFrom::Start(startSize as u64))?;
-            sourceFile.read_exact(&mut configBuffer)?;
+/// 移动文件中的一段数据(源与目标区域可以重叠)
+/// # 参数
+/// 1. 文件
+/// 2. 数据起始位置
+/// 3. 目标位置
+/// 4. 数据长度
+pub fn moveData(file: &mut File, from: u64, to: u64, length: u64) -> Result<(), Box<dyn Error>> {
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut moved = 0;
+    while moved < length {
+        let size = std::cmp::min(BUFFER_SIZE as u64, length - moved);
+        // 向前移动时从头部开始复制，向后移动时从尾部开始复制，避免覆盖尚未移动的数据
+        let offset = if to <= from { moved } else { length - moved - size };
+        file.seek(SeekFrom::Start(from + offset))?;
+        file.read_exact(&mut buffer[..size as usize])?;
+        file.seek(SeekFrom::Start(to + offset))?;
+        file.write_all(&buffer[..size as usize])?;
+        moved += size;
+    }
+    Ok(())
+}
 
-            let config = ResourceHead::from(&configBuffer);
-            if let Err(_e) = config {
-                continue;
-            }
-            let config = config.unwrap();
-            // println!("在标识头位置: {} 找到配置 {:?}", startSize,config);
+/// 删除资源
+/// # 参数
+/// 1. 目标文件
+/// 2. 资源ID
+pub fn removeResource(targetFilePath: &Path, id: &str) -> Result<(), Box<dyn Error>> {
+    Carrier::open(targetFilePath)?.remove(id)
+}
 
-            callback(startSize, &config);
-            configs.push(config);
-            sourceFile.seek(SeekFrom::Start(oldSize))?;
-        }
+/// 在指定位置读取资源文件头
+/// # 返回值
+/// (资源文件头, 文件头长度)
+pub fn readResourceHead(sourceFile: &mut File, offset: u64) -> Result<(ResourceHead, u64), Box<dyn Error>> {
+    // 旧版本文件头长度较短，读取时可能到达文件末尾
+    let mut configBuffer: Vec<u8> = Vec::new();
+    sourceFile.seek(SeekFrom::Start(offset))?;
+    (&mut *sourceFile).take(MAX_HEAD_LENGTH).read_to_end(&mut configBuffer)?;
+    let (config, headLen) = ResourceHead::parse(&configBuffer)?;
+    Ok((config, headLen as u64))
+}
 
-        // 如果文件搜寻完毕
-        if nbytes < &buffer.len() {
-            break;
-        }
-    }
-    Ok(configs)
+/// 寻找资源 - 从头至尾
+/// # 参数
+/// 1. 载体文件
+/// # 返回值
+/// 资源条目 数组
+pub fn findResources(sourceFile: &mut File) -> Result<Vec<ResourceEntry>, Box<dyn Error>> {
+    // 可执行文件只扫描附加数据，避免将代码或只读数据中的相同字节误认为资源
+    let start = ImageInfo::detect(sourceFile).imageEnd;
+    findResourcesFrom(sourceFile, start)
+}
+
+/// 从指定位置开始扫描文件中的资源
+/// # 参数
+/// 1. 目标文件
+/// 2. 扫描起始位置
+/// # 返回值
+/// 资源条目 数组(按位置排序)
+pub fn findResourcesFrom(sourceFile: &mut File, start: u64) -> Result<Vec<ResourceEntry>, Box<dyn Error>> {
+    let mut entries = ResourceScanner::new(sourceFile, start).collect::<Result<Vec<ResourceEntry>, Box<dyn Error>>>()?;
+    // v1 标识位于文件头起始位置之后，跨块时可能晚于之后的 v2 文件头找到
+    entries.sort_by_key(|entry| entry.offset);
+    Ok(entries)
+}
+
+/// 寻找资源配置 - 从头至尾
+/// # 参数
+/// 1. 目标文件
+/// 2. 回调函数(配置位置, 资源配置)
+/// # 返回值
+/// 资源配置 数组
+pub fn findResourcesConfig(targetFilePath: &Path, callback: fn(startSize: usize, config: &ResourceHead)) -> Result<Vec<ResourceHead>, Box<dyn Error>> {
+    let mut sourceFile = File::open(targetFilePath)?;
+    let entries = findResources(&mut sourceFile)?;
+    Ok(entries.into_iter().map(|entry| {
+        callback(entry.offset as usize, &entry.head);
+        entry.head
+    }).collect())
 }
 
 /// 寻找字节（速度较慢）
@@ -337,33 +881,56 @@ fn _find_subsequence(haystack: &[u8], needle: &[u8]) -> Option<usize> {
     haystack.windows(needle.len()).position(|window| window == needle)
 }
 
-/// 比较版本号大小
-fn compareVersion(version1: &str, version2: &str) -> Ordering {
-    let nums1: Vec<&str> = version1.split('.').collect();
-    let nums2: Vec<&str> = version2.split('.').collect();
-    let n1 = nums1.len();
-    let n2 = nums2.len();
+/// 解析版本号(忽略 "-"、"+" 之后的预发布及构建信息)
+/// # 返回值
+/// 版本号的各个数字，包含非数字部分时返回错误
+fn parseVersion(version: &str) -> Result<Vec<u64>, Box<dyn Error>> {
+    let core = version.split(['-', '+']).next().unwrap_or_default();
+    core.split('.').map(|part| part.parse::<u64>()).collect::<Result<Vec<u64>, _>>()
+        .map_err(|_| Box::from(AppenderError::InvalidHeader(format!("Invalid resource version \"{}\"", version))))
+}
 
-    // 比较版本
-    for i in 0..std::cmp::max(n1, n2) {
-        let i1 = if i < n1 {
-            nums1[i].parse::<i32>().unwrap()
-        } else {
-            0
-        };
-        let i2 = if i < n2 {
-            nums2[i].parse::<i32>().unwrap()
-        } else {
-            0
-        };
-        if i1 != i2 {
-            return if i1 > i2 {
-                Ordering::Greater
-            } else {
-                Ordering::Less
-            };
+/// 比较版本号大小(缺少的部分视为 0)
+pub fn compareVersion(version1: &str, version2: &str) -> Result<Ordering, Box<dyn Error>> {
+    let nums1 = parseVersion(version1)?;
+    let nums2 = parseVersion(version2)?;
+    for i in 0..std::cmp::max(nums1.len(), nums2.len()) {
+        let ordering = nums1.get(i).unwrap_or(&0).cmp(nums2.get(i).unwrap_or(&0));
+        if ordering.is_ne() {
+            return Ok(ordering);
         }
     }
     // 版本相等
-    Ordering::Equal
+    Ok(Ordering::Equal)
+}
+
+/// 资源版本与当前版本的兼容性
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum VersionCompatibility {
+    /// 当前版本
+    Current,
+    /// 旧版本(可以读取，可以通过迁移转换为当前版本)
+    Older,
+    /// 主版本相同的新次版本(可以读取，新增的信息会被忽略)
+    NewerMinor,
+    /// 新的主版本(无法读取)
+    Incompatible,
+}
+
+/// 判断资源版本与当前版本的兼容性(语义化版本: 主版本相同即可读取)
+pub fn versionCompatibility(version: &str) -> Result<VersionCompatibility, Box<dyn Error>> {
+    let current = parseVersion(RESOURCE_VERSION)?;
+    let nums = parseVersion(version)?;
+    let major = nums.first().copied().unwrap_or(0);
+    Ok(match major.cmp(&current[0]) {
+        Ordering::Greater => VersionCompatibility::Incompatible,
+        // 旧的主版本按旧格式读取
+        Ordering::Less => VersionCompatibility::Older,
+        Ordering::Equal => match compareVersion(version, RESOURCE_VERSION)? {
+            Ordering::Greater if nums.get(1).copied().unwrap_or(0) > current[1] => VersionCompatibility::NewerMinor,
+            // 修订版本不影响文件头格式
+            Ordering::Greater | Ordering::Equal => VersionCompatibility::Current,
+            Ordering::Less => VersionCompatibility::Older,
+        },
+    })
 }
@@ -0,0 +1,322 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use serde::{Serialize, Deserialize};
+use crate::error::AppenderError;
+
+/// 加密密钥长度(AES-256)
+pub const KEY_LENGTH: usize = 32;
+
+/// 盐长度(用于 Argon2 由密码派生密钥)
+pub const SALT_LENGTH: usize = 16;
+
+/// 随机数前缀长度(STREAM 分块加密: 12 字节随机数 = 7 字节前缀 + 4 字节分块序号 + 1 字节末块标识)
+pub const NONCE_LENGTH: usize = 7;
+
+/// 加密分块大小(64KB)，每个分块带有 16 字节认证标签
+pub const CHUNK_SIZE: usize = 1024 * 64;
+
+/// 认证标签长度
+#[cfg(feature = "encryption")]
+const TAG_LENGTH: usize = 16;
+
+/// 加密算法
+///
+/// 序列化时使用变体序号，新增的加密算法只能追加在末尾
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Cipher {
+    /// 不加密
+    #[default]
+    None,
+    /// AES-256-GCM(分块认证加密)
+    #[serde(rename = "aes-256-gcm")]
+    Aes256Gcm,
+}
+
+impl Cipher {
+    /// 获取加密算法名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cipher::None => "none",
+            Cipher::Aes256Gcm => "aes-256-gcm",
+        }
+    }
+
+    /// 获取加密算法编号(v2 资源文件头中保存的编号，与序列化的变体序号相同)
+    pub fn id(&self) -> u8 {
+        match self {
+            Cipher::None => 0,
+            Cipher::Aes256Gcm => 1,
+        }
+    }
+
+    /// 由加密算法编号解析
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(Cipher::None),
+            1 => Some(Cipher::Aes256Gcm),
+            _ => None,
+        }
+    }
+}
+
+/// 加密密钥来源
+#[derive(Clone)]
+pub enum Secret {
+    /// 密码(使用 Argon2 派生密钥)
+    Password(String),
+    /// 密钥(密钥文件内容)
+    Key(Vec<u8>),
+}
+
+impl Secret {
+    /// 从密钥文件读取密钥(文件内容必须为 32 字节)
+    pub fn from_key_file(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let key = fs::read(path)?;
+        if key.len() != KEY_LENGTH {
+            return Err(Box::from(AppenderError::InvalidArgument(format!("The key file must contain exactly {} bytes", KEY_LENGTH))));
+        }
+        Ok(Secret::Key(key))
+    }
+
+    /// 是否需要盐(密码需要加盐派生密钥)
+    pub fn needSalt(&self) -> bool {
+        matches!(self, Secret::Password(_))
+    }
+
+    /// 派生加密密钥(密码使用 Argon2 加盐派生，密钥原样返回)
+    ///
+    /// 派生密钥较慢，多次解密同一资源时可以先派生一次，再使用返回的密钥
+    pub fn derive(&self, salt: &[u8]) -> Result<Secret, Box<dyn Error>> {
+        #[cfg(feature = "encryption")]
+        {
+            Ok(Secret::Key(imp::deriveKey(self, salt)?))
+        }
+        #[cfg(not(feature = "encryption"))]
+        {
+            let _ = salt;
+            Err(notSupported())
+        }
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // 不输出密码及密钥内容
+        match self {
+            Secret::Password(_) => write!(f, "Password(***)"),
+            Secret::Key(_) => write!(f, "Key(***)"),
+        }
+    }
+}
+
+/// 篡改或密钥错误时的提示
+#[cfg(feature = "encryption")]
+const TAMPERED_MESSAGE: &str = "The resource has been tampered with or the password/key is incorrect";
+
+#[cfg(feature = "encryption")]
+mod imp {
+    use std::error::Error;
+    use std::io::{self, Read, Write};
+    use aes_gcm::{Aes256Gcm, KeyInit};
+    use aes_gcm::aead::{OsRng, rand_core::RngCore, stream::{EncryptorBE32, DecryptorBE32}};
+    use argon2::Argon2;
+    use super::{Secret, KEY_LENGTH, NONCE_LENGTH, CHUNK_SIZE, TAG_LENGTH, TAMPERED_MESSAGE};
+    use crate::error::AppenderError;
+
+    /// 生成随机字节(盐、随机数)
+    pub fn randomBytes(length: usize) -> Vec<u8> {
+        let mut bytes = vec![0u8; length];
+        OsRng.fill_bytes(&mut bytes);
+        bytes
+    }
+
+    /// 派生加密密钥
+    pub fn deriveKey(secret: &Secret, salt: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+        match secret {
+            Secret::Password(password) => {
+                let mut key = vec![0u8; KEY_LENGTH];
+                Argon2::default().hash_password_into(password.as_bytes(), salt, &mut key)
+                    .map_err(|e| AppenderError::InvalidHeader(format!("Key derivation failed: {}", e)))?;
+                Ok(key)
+            }
+            Secret::Key(key) => Ok(key.clone()),
+        }
+    }
+
+    /// 创建加密算法实例
+    fn newCipher(secret: &Secret, salt: &[u8]) -> Result<Aes256Gcm, Box<dyn Error>> {
+        let key = deriveKey(secret, salt)?;
+        Ok(Aes256Gcm::new_from_slice(&key).map_err(|_e| AppenderError::InvalidArgument("Invalid key length".to_string()))?)
+    }
+
+    /// 加密写入器(按分块加密后写入，必须调用 finish 写入最后一个分块)
+    pub struct EncryptWriter<W: Write> {
+        inner: W,
+        encryptor: Option<EncryptorBE32<Aes256Gcm>>,
+        buffer: Vec<u8>,
+    }
+
+    impl<W: Write> EncryptWriter<W> {
+        pub fn new(inner: W, secret: &Secret, salt: &[u8], nonce: &[u8]) -> Result<Self, Box<dyn Error>> {
+            let encryptor = EncryptorBE32::from_aead(newCipher(secret, salt)?, nonce.into());
+            Ok(EncryptWriter { inner, encryptor: Some(encryptor), buffer: Vec::with_capacity(CHUNK_SIZE) })
+        }
+
+        /// 加密并写入最后一个分块
+        pub fn finish(mut self) -> io::Result<W> {
+            let encryptor = self.encryptor.take().unwrap();
+            let chunk = encryptor.encrypt_last(self.buffer.as_slice()).map_err(|_e| io::Error::other("Encryption failed"))?;
+            self.inner.write_all(&chunk)?;
+            Ok(self.inner)
+        }
+    }
+
+    impl<W: Write> Write for EncryptWriter<W> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            // 分块已满且仍有数据时才写出，保证最后一个分块由 finish 写出
+            if self.buffer.len() == CHUNK_SIZE {
+                let chunk = self.encryptor.as_mut().unwrap().encrypt_next(self.buffer.as_slice()).map_err(|_e| io::Error::other("Encryption failed"))?;
+                self.inner.write_all(&chunk)?;
+                self.buffer.clear();
+            }
+            let nbytes = std::cmp::min(buf.len(), CHUNK_SIZE - self.buffer.len());
+            self.buffer.extend_from_slice(&buf[..nbytes]);
+            Ok(nbytes)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    /// 解密读取器(逐个分块认证并解密)
+    pub struct DecryptReader<R: Read> {
+        inner: R,
+        decryptor: Option<DecryptorBE32<Aes256Gcm>>,
+        /// 已读取但尚未解密的数据
+        pending: Vec<u8>,
+        /// 已解密但尚未读出的数据
+        plain: Vec<u8>,
+        position: usize,
+    }
+
+    impl<R: Read> DecryptReader<R> {
+        pub fn new(inner: R, secret: &Secret, salt: &[u8], nonce: &[u8]) -> Result<Self, Box<dyn Error>> {
+            if nonce.len() != NONCE_LENGTH {
+                return Err(Box::from(AppenderError::InvalidHeader("Invalid resource nonce".to_string())));
+            }
+            let decryptor = DecryptorBE32::from_aead(newCipher(secret, salt)?, nonce.into());
+            Ok(DecryptReader { inner, decryptor: Some(decryptor), pending: Vec::new(), plain: Vec::new(), position: 0 })
+        }
+
+        /// 读取并解密下一个分块
+        fn nextChunk(&mut self) -> io::Result<()> {
+            // 多读取一个字节以判断当前分块是否为最后一个分块
+            let chunkLength = CHUNK_SIZE + TAG_LENGTH;
+            while self.pending.len() <= chunkLength {
+                let mut buffer = vec![0u8; chunkLength + 1 - self.pending.len()];
+                let nbytes = self.inner.read(&mut buffer)?;
+                if nbytes == 0 { break; }
+                self.pending.extend_from_slice(&buffer[..nbytes]);
+            }
+
+            let tampered = |_e| io::Error::new(io::ErrorKind::InvalidData, TAMPERED_MESSAGE);
+            self.plain = if self.pending.len() > chunkLength {
+                let rest = self.pending.split_off(chunkLength);
+                let chunk = std::mem::replace(&mut self.pending, rest);
+                self.decryptor.as_mut().unwrap().decrypt_next(chunk.as_slice()).map_err(tampered)?
+            } else {
+                let chunk = std::mem::take(&mut self.pending);
+                self.decryptor.take().unwrap().decrypt_last(chunk.as_slice()).map_err(tampered)?
+            };
+            self.position = 0;
+            Ok(())
+        }
+    }
+
+    impl<R: Read> Read for DecryptReader<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            while self.position == self.plain.len() {
+                // 最后一个分块已解密
+                if self.decryptor.is_none() {
+                    return Ok(0);
+                }
+                self.nextChunk()?;
+            }
+            let nbytes = std::cmp::min(buf.len(), self.plain.len() - self.position);
+            buf[..nbytes].copy_from_slice(&self.plain[self.position..self.position + nbytes]);
+            self.position += nbytes;
+            Ok(nbytes)
+        }
+    }
+}
+
+/// 当前程序是否支持加密(编译时启用了 "encryption" 特性)
+pub fn supported() -> bool {
+    cfg!(feature = "encryption")
+}
+
+/// 生成加密参数
+/// # 返回值
+/// (盐, 随机数前缀)，使用密钥文件时不需要盐
+pub fn newParameters(secret: &Secret) -> Result<(Vec<u8>, Vec<u8>), Box<dyn Error>> {
+    #[cfg(feature = "encryption")]
+    {
+        let salt = if secret.needSalt() { imp::randomBytes(SALT_LENGTH) } else { Vec::new() };
+        Ok((salt, imp::randomBytes(NONCE_LENGTH)))
+    }
+    #[cfg(not(feature = "encryption"))]
+    {
+        let _ = secret;
+        Err(notSupported())
+    }
+}
+
+/// 加密写入
+/// # 参数
+/// 1. 输出流
+/// 2. 密钥来源
+/// 3. 盐
+/// 4. 随机数前缀
+/// 5. 写入函数(向加密写入器写入明文)
+pub fn encrypt<W: Write>(output: W, secret: &Secret, salt: &[u8], nonce: &[u8], write: impl FnOnce(&mut dyn Write) -> Result<u64, Box<dyn Error>>) -> Result<u64, Box<dyn Error>> {
+    #[cfg(feature = "encryption")]
+    {
+        let mut writer = imp::EncryptWriter::new(output, secret, salt, nonce)?;
+        let size = write(&mut writer)?;
+        writer.finish()?;
+        Ok(size)
+    }
+    #[cfg(not(feature = "encryption"))]
+    {
+        let _ = (output, secret, salt, nonce, write);
+        Err(notSupported())
+    }
+}
+
+/// 创建解密读取器
+/// # 参数
+/// 1. 输入流(密文)
+/// 2. 密钥来源
+/// 3. 盐
+/// 4. 随机数前缀
+pub fn decrypt<'a>(input: Box<dyn Read + 'a>, secret: &Secret, salt: &[u8], nonce: &[u8]) -> Result<Box<dyn Read + 'a>, Box<dyn Error>> {
+    #[cfg(feature = "encryption")]
+    {
+        Ok(Box::new(imp::DecryptReader::new(input, secret, salt, nonce)?))
+    }
+    #[cfg(not(feature = "encryption"))]
+    {
+        let _ = (input, secret, salt, nonce);
+        Err(notSupported())
+    }
+}
+
+/// 当前程序不支持加密
+pub(crate) fn notSupported() -> Box<dyn Error> {
+    Box::from(AppenderError::InvalidArgument("Encryption is not supported by this build, please enable the \"encryption\" feature".to_string()))
+}
@@ -0,0 +1,125 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+use crate::error::AppenderError;
+use crate::util::{readAt, readUInt};
+
+/// ELF 文件标识 "\x7FELF"
+const ELF_MAGIC: [u8; 4] = [0x7F, 0x45, 0x4C, 0x46];
+
+/// 32位 ELF
+const ELF_CLASS_32: u8 = 1;
+
+/// 64位 ELF
+const ELF_CLASS_64: u8 = 2;
+
+/// 小端序
+const ELF_DATA_LSB: u8 = 1;
+
+/// 大端序
+const ELF_DATA_MSB: u8 = 2;
+
+/// 不占用文件空间的节(.bss)
+const SHT_NOBITS: u64 = 8;
+
+/// ELF 文件映像信息
+///
+/// 程序头表、节头表及所有段、节数据的结束位置即映像结束位置，之后的数据不会被加载
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElfImage {
+    /// 是否为 64位 ELF
+    pub is64: bool,
+    /// 是否为大端序
+    pub bigEndian: bool,
+    /// 映像结束位置(附加数据起始位置)
+    pub imageEnd: u64,
+}
+
+impl ElfImage {
+    /// 解析 ELF 文件头、程序头表及节头表
+    /// # 返回值
+    /// 不是 ELF 文件时返回 None，ELF 文件头损坏时返回错误
+    pub fn parse(file: &mut File) -> Result<Option<Self>, Box<dyn Error>> {
+        let fileLength = file.seek(SeekFrom::End(0))?;
+        if fileLength < 0x34 || readAt(file, 0, 4)? != ELF_MAGIC {
+            return Ok(None);
+        }
+        let ident = readAt(file, 0, 16)?;
+        let is64 = match ident[4] {
+            ELF_CLASS_32 => false,
+            ELF_CLASS_64 => true,
+            class => return Err(Box::from(AppenderError::InvalidHeader(format!("Unknown ELF class {}", class)))),
+        };
+        let bigEndian = match ident[5] {
+            ELF_DATA_LSB => false,
+            ELF_DATA_MSB => true,
+            data => return Err(Box::from(AppenderError::InvalidHeader(format!("Unknown ELF data encoding {}", data)))),
+        };
+
+        // ELF 文件头(32位与64位的字段位置不同)
+        let header = readAt(file, 0, if is64 { 0x40 } else { 0x34 })?;
+        let read = |offset: usize, size: usize| readUInt(&header, offset, size, bigEndian);
+        let (phoff, shoff, ehsize, phentsize, phnum, shentsize, shnum) = if is64 {
+            (read(0x20, 8), read(0x28, 8), read(0x34, 2), read(0x36, 2), read(0x38, 2), read(0x3A, 2), read(0x3C, 2))
+        } else {
+            (read(0x1C, 4), read(0x20, 4), read(0x28, 2), read(0x2A, 2), read(0x2C, 2), read(0x2E, 2), read(0x30, 2))
+        };
+
+        let mut imageEnd = ehsize;
+
+        // 程序头表及各段数据
+        if phoff != 0 && phnum != 0 {
+            let table = readTable(file, phoff, phentsize, phnum, if is64 { 40 } else { 20 }, fileLength)?;
+            imageEnd = imageEnd.max(phoff + phentsize * phnum);
+            for entry in table.chunks_exact(phentsize as usize) {
+                let (offset, filesz) = if is64 {
+                    (readUInt(entry, 8, 8, bigEndian), readUInt(entry, 32, 8, bigEndian))
+                } else {
+                    (readUInt(entry, 4, 4, bigEndian), readUInt(entry, 16, 4, bigEndian))
+                };
+                if filesz != 0 {
+                    imageEnd = imageEnd.max(offset.saturating_add(filesz));
+                }
+            }
+        }
+
+        // 节头表及各节数据
+        if shoff != 0 {
+            let mut shnum = shnum;
+            // 节数量过多时，实际数量保存在第一个节头的 sh_size 中
+            if shnum == 0 {
+                let first = readTable(file, shoff, shentsize, 1, if is64 { 40 } else { 24 }, fileLength)?;
+                shnum = if is64 { readUInt(&first, 32, 8, bigEndian) } else { readUInt(&first, 20, 4, bigEndian) };
+            }
+            let table = readTable(file, shoff, shentsize, shnum, if is64 { 40 } else { 24 }, fileLength)?;
+            imageEnd = imageEnd.max(shoff + shentsize * shnum);
+            for entry in table.chunks_exact(shentsize as usize) {
+                let (shType, offset, size) = if is64 {
+                    (readUInt(entry, 4, 4, bigEndian), readUInt(entry, 24, 8, bigEndian), readUInt(entry, 32, 8, bigEndian))
+                } else {
+                    (readUInt(entry, 4, 4, bigEndian), readUInt(entry, 16, 4, bigEndian), readUInt(entry, 20, 4, bigEndian))
+                };
+                if shType != SHT_NOBITS && size != 0 {
+                    imageEnd = imageEnd.max(offset.saturating_add(size));
+                }
+            }
+        }
+
+        Ok(Some(ElfImage { is64, bigEndian, imageEnd: imageEnd.min(fileLength) }))
+    }
+}
+
+/// 读取程序头表或节头表
+/// # 参数
+/// 1. 文件
+/// 2. 表起始位置
+/// 3. 表项长度
+/// 4. 表项数量
+/// 5. 表项最小长度(需要读取的字段范围)
+/// 6. 文件长度
+fn readTable(file: &mut File, offset: u64, entrySize: u64, count: u64, minSize: u64, fileLength: u64) -> Result<Vec<u8>, Box<dyn Error>> {
+    match entrySize.checked_mul(count).and_then(|size| offset.checked_add(size)) {
+        Some(end) if entrySize >= minSize && end <= fileLength => readAt(file, offset, (entrySize * count) as usize),
+        _ => Err(Box::from(AppenderError::Corrupt("The ELF header table is out of range".to_string()))),
+    }
+}
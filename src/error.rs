@@ -0,0 +1,139 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+/// 其他错误的退出码
+pub const EXIT_FAILURE: i32 = 1;
+/// 参数无效的退出码
+pub const EXIT_INVALID_ARGUMENT: i32 = 2;
+/// 资源不存在的退出码
+pub const EXIT_NOT_FOUND: i32 = 3;
+/// 资源损坏的退出码
+pub const EXIT_CORRUPT: i32 = 4;
+/// 资源版本不兼容的退出码
+pub const EXIT_VERSION_MISMATCH: i32 = 5;
+/// 资源ID已存在的退出码
+pub const EXIT_DUPLICATE_ID: i32 = 6;
+/// 资源文件头或可执行文件头无效的退出码
+pub const EXIT_INVALID_HEADER: i32 = 7;
+/// 读写文件失败的退出码
+pub const EXIT_IO: i32 = 8;
+/// 输出文件已存在的退出码
+pub const EXIT_CONFLICT: i32 = 9;
+
+/// 可区分的错误类型
+///
+/// 函数仍返回 `Box<dyn Error>`，需要区分错误类型时通过 `downcast_ref::<AppenderError>()` 判断(见 [`exitCode`])
+#[derive(Debug)]
+pub enum AppenderError {
+    /// 参数无效(压缩等级、对齐长度等)
+    InvalidArgument(String),
+    /// 资源不存在
+    NotFound(String),
+    /// 资源不完整或已损坏(尾部标识、大小、摘要不符，解密或解压失败)，或可执行文件中的表超出文件范围
+    Corrupt(String),
+    /// 资源版本不兼容(需要升级程序)
+    VersionMismatch(String),
+    /// 资源ID已存在
+    DuplicateId(String),
+    /// 资源文件头或可执行文件头(PE、ELF、Mach-O)无效
+    InvalidHeader(String),
+    /// 读写文件失败
+    Io(io::Error),
+    /// 输出文件已存在(释放资源时)
+    Conflict(String),
+}
+
+impl AppenderError {
+    /// 进程退出码(各错误类型不同)
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppenderError::InvalidArgument(_) => EXIT_INVALID_ARGUMENT,
+            AppenderError::NotFound(_) => EXIT_NOT_FOUND,
+            AppenderError::Corrupt(_) => EXIT_CORRUPT,
+            AppenderError::VersionMismatch(_) => EXIT_VERSION_MISMATCH,
+            AppenderError::DuplicateId(_) => EXIT_DUPLICATE_ID,
+            AppenderError::InvalidHeader(_) => EXIT_INVALID_HEADER,
+            AppenderError::Io(_) => EXIT_IO,
+            AppenderError::Conflict(_) => EXIT_CONFLICT,
+        }
+    }
+
+    /// 解码资源数据时的读取错误: 数据无效(解压、解密失败)或提前结束视为资源损坏，其他为读写失败
+    pub fn from_decode(error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::InvalidData | io::ErrorKind::InvalidInput | io::ErrorKind::UnexpectedEof => AppenderError::Corrupt(error.to_string()),
+            _ => AppenderError::Io(error),
+        }
+    }
+}
+
+impl fmt::Display for AppenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppenderError::InvalidArgument(message)
+            | AppenderError::NotFound(message)
+            | AppenderError::Corrupt(message)
+            | AppenderError::VersionMismatch(message)
+            | AppenderError::DuplicateId(message)
+            | AppenderError::InvalidHeader(message)
+            | AppenderError::Conflict(message) => write!(f, "{}", message),
+            AppenderError::Io(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl Error for AppenderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AppenderError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AppenderError {
+    fn from(error: io::Error) -> Self {
+        AppenderError::Io(error)
+    }
+}
+
+/// 是否为资源版本不兼容的错误
+pub fn isVersionMismatch(error: &(dyn Error + 'static)) -> bool {
+    matches!(error.downcast_ref::<AppenderError>(), Some(AppenderError::VersionMismatch(_)))
+}
+
+/// 在错误信息前添加说明(如出错的文件)，保留错误类型及退出码
+pub fn withContext(error: Box<dyn Error>, context: &str) -> Box<dyn Error> {
+    let message = format!("{}: {}", context, error);
+    let error = match error.downcast::<AppenderError>() {
+        Ok(error) => match *error {
+            AppenderError::InvalidArgument(_) => AppenderError::InvalidArgument(message),
+            AppenderError::NotFound(_) => AppenderError::NotFound(message),
+            AppenderError::Corrupt(_) => AppenderError::Corrupt(message),
+            AppenderError::VersionMismatch(_) => AppenderError::VersionMismatch(message),
+            AppenderError::DuplicateId(_) => AppenderError::DuplicateId(message),
+            AppenderError::InvalidHeader(_) => AppenderError::InvalidHeader(message),
+            AppenderError::Io(error) => AppenderError::Io(io::Error::new(error.kind(), message)),
+            AppenderError::Conflict(_) => AppenderError::Conflict(message),
+        },
+        Err(error) => match error.downcast::<io::Error>() {
+            Ok(error) => AppenderError::Io(io::Error::new(error.kind(), message)),
+            Err(_error) => return Box::from(message),
+        },
+    };
+    Box::from(error)
+}
+
+/// 错误对应的进程退出码
+///
+/// [`AppenderError`] 按类型区分，未转换的 `io::Error` 视为读写失败，其他错误为 [`EXIT_FAILURE`]
+pub fn exitCode(error: &(dyn Error + 'static)) -> i32 {
+    if let Some(error) = error.downcast_ref::<AppenderError>() {
+        return error.exit_code();
+    }
+    if error.downcast_ref::<io::Error>().is_some() {
+        return EXIT_IO;
+    }
+    EXIT_FAILURE
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+use std::fs::File;
+use serde::Serialize;
+use crate::pe::PeImage;
+use crate::elf::ElfImage;
+use crate::macho::MachOImage;
+
+/// 载体文件格式
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CarrierFormat {
+    /// 无法识别的格式(整个文件均可能包含资源)
+    #[default]
+    Raw,
+    /// Windows 可执行文件(PE)
+    Pe,
+    /// Linux 可执行文件(ELF)
+    Elf,
+    /// macOS 可执行文件(Mach-O)
+    #[serde(rename = "mach-o")]
+    MachO,
+    /// macOS 通用二进制(包含多个架构的 Mach-O)
+    #[serde(rename = "mach-o-fat")]
+    FatMachO,
+}
+
+impl CarrierFormat {
+    /// 获取格式名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            CarrierFormat::Raw => "raw",
+            CarrierFormat::Pe => "PE",
+            CarrierFormat::Elf => "ELF",
+            CarrierFormat::MachO => "Mach-O",
+            CarrierFormat::FatMachO => "Mach-O (fat)",
+        }
+    }
+}
+
+impl fmt::Display for CarrierFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// 载体文件映像信息
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ImageInfo {
+    /// 载体文件格式
+    pub format: CarrierFormat,
+    /// 映像结束位置(附加数据起始位置)，无法识别的格式为 0
+    pub imageEnd: u64,
+    /// 是否带有签名(PE 的 Authenticode、Mach-O 的 LC_CODE_SIGNATURE)
+    pub signed: bool,
+}
+
+impl ImageInfo {
+    /// 识别载体文件格式
+    ///
+    /// 文件头损坏时按无法识别的格式处理(扫描整个文件)
+    pub fn detect(file: &mut File) -> Self {
+        if let Ok(Some(image)) = PeImage::parse(file) {
+            return ImageInfo { format: CarrierFormat::Pe, imageEnd: image.imageEnd, signed: image.isSigned() };
+        }
+        if let Ok(Some(image)) = ElfImage::parse(file) {
+            return ImageInfo { format: CarrierFormat::Elf, imageEnd: image.imageEnd, signed: false };
+        }
+        if let Ok(Some(image)) = MachOImage::parse(file) {
+            let format = if image.fat { CarrierFormat::FatMachO } else { CarrierFormat::MachO };
+            return ImageInfo { format, imageEnd: image.imageEnd, signed: image.isSigned() };
+        }
+        ImageInfo::default()
+    }
+}
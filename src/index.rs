@@ -0,0 +1,106 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{Write, Read, SeekFrom, Seek};
+use serde::{Serialize, Deserialize};
+use crate::core::{CompressMode, ResourceEntry};
+
+/// 资源索引尾部标识
+pub const INDEX_IDENTIFIER: [u8; 8] = [0x89, 0x4F, 0x44, 0x49, 0x4E, 0x44, 0x45, 0x58];
+
+/// 资源索引尾部长度(索引位置 + 索引长度 + 尾部标识)
+pub const FOOTER_LENGTH: u64 = 8 + 8 + INDEX_IDENTIFIER.len() as u64;
+
+/// 资源索引条目
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IndexEntry {
+    /// 资源ID
+    pub Id: String,
+    /// 资源文件头起始位置
+    pub Offset: u64,
+    /// 资源文件头长度
+    pub HeadLength: u64,
+    /// 资源长度
+    pub Length: u64,
+    /// 压缩模式
+    pub Compress: CompressMode,
+}
+
+/// 资源索引(目录)
+///
+/// 载体文件末尾结构:
+/// ```text
+/// | 资源... | 资源索引(bincode) | 索引位置(u64) | 索引长度(u64) | 尾部标识 |
+/// ```
+/// 查找资源时只需读取末尾的固定长度尾部即可定位索引，无需扫描整个文件
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ResourceIndex {
+    /// 索引条目
+    pub Entries: Vec<IndexEntry>,
+}
+
+impl ResourceIndex {
+    /// 由资源条目生成索引
+    pub fn fromEntries(entries: &[ResourceEntry]) -> Self {
+        ResourceIndex {
+            Entries: entries.iter().map(|entry| IndexEntry {
+                Id: entry.head.getId().to_string(),
+                Offset: entry.offset,
+                HeadLength: entry.headLen,
+                Length: entry.head.getLength(),
+                Compress: entry.head.getCompressMode(),
+            }).collect(),
+        }
+    }
+
+    /// 读取载体文件末尾的资源索引
+    /// # 返回值
+    /// (索引起始位置, 资源索引)，没有索引时返回 None
+    pub fn read(file: &mut File) -> Result<Option<(u64, ResourceIndex)>, Box<dyn Error>> {
+        let fileLength = file.seek(SeekFrom::End(0))?;
+        if fileLength < FOOTER_LENGTH {
+            return Ok(None);
+        }
+
+        // 读取尾部
+        let mut footer = [0u8; FOOTER_LENGTH as usize];
+        file.seek(SeekFrom::Start(fileLength - FOOTER_LENGTH))?;
+        file.read_exact(&mut footer)?;
+        if footer[16..] != INDEX_IDENTIFIER {
+            return Ok(None);
+        }
+        let mut number = [0u8; 8];
+        number.copy_from_slice(&footer[..8]);
+        let indexOffset = u64::from_le_bytes(number);
+        number.copy_from_slice(&footer[8..16]);
+        let indexLength = u64::from_le_bytes(number);
+
+        // 索引必须紧邻尾部
+        if indexOffset.checked_add(indexLength) != Some(fileLength - FOOTER_LENGTH) {
+            return Ok(None);
+        }
+
+        let mut indexBuffer = vec![0u8; indexLength as usize];
+        file.seek(SeekFrom::Start(indexOffset))?;
+        file.read_exact(&mut indexBuffer)?;
+        match bincode::deserialize(&indexBuffer) {
+            Ok(index) => Ok(Some((indexOffset, index))),
+            Err(_e) => Ok(None),
+        }
+    }
+
+    /// 资源索引及尾部的总长度
+    pub fn length(&self) -> Result<u64, Box<dyn Error>> {
+        Ok(bincode::serialized_size(self)? + FOOTER_LENGTH)
+    }
+
+    /// 在当前位置写入资源索引及尾部
+    pub fn write(&self, file: &mut File) -> Result<(), Box<dyn Error>> {
+        let indexOffset = file.stream_position()?;
+        let index = bincode::serialize(self)?;
+        file.write_all(&index)?;
+        file.write_all(&indexOffset.to_le_bytes())?;
+        file.write_all(&(index.len() as u64).to_le_bytes())?;
+        file.write_all(&INDEX_IDENTIFIER)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+// 禁用变量命名警告
+#![allow(non_snake_case)]
+// 运行使用基准测试
+#![cfg_attr(test, feature(test))]
+
+//! Appender - 增加、读取、释放附加数据(Overlay)资源
+//!
+//! ```no_run
+//! use Appender::Carrier;
+//!
+//! let mut carrier = Carrier::open("Program.exe").unwrap();
+//! for entry in carrier.entries().unwrap() {
+//!     println!("{} {}", entry.head.getId(), entry.head.getName());
+//! }
+//! let data = carrier.read_to_vec("Archive").unwrap();
+//! ```
+//!
+//! 读取当前程序附加的资源(不释放到磁盘):
+//!
+//! ```no_run
+//! use std::io::Read;
+//! use Appender::Carrier;
+//!
+//! let mut reader = Carrier::current_exe().unwrap().open_resource("Archive").unwrap();
+//! let mut data = Vec::new();
+//! reader.read_to_end(&mut data).unwrap();
+//! ```
+//!
+//! 启用 `mmap` 特性后，可以零拷贝读取未压缩且未加密的资源:
+//!
+//! ```ignore
+//! use Appender::Carrier;
+//!
+//! let mapped = Carrier::current_exe().unwrap().map().unwrap();
+//! let font: &[u8] = mapped.get("Font").unwrap();
+//! ```
+
+#[macro_use]
+extern crate lazy_static;
+
+pub mod util;
+pub mod error;
+pub mod core;
+pub mod codec;
+pub mod crypto;
+pub mod index;
+pub mod pe;
+pub mod elf;
+pub mod macho;
+pub mod format;
+pub mod carrier;
+pub mod reader;
+pub mod scanner;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+pub mod list;
+
+#[cfg(test)]
+mod tests;
+
+pub use crate::carrier::{Carrier, ConflictPolicy, ExportReport};
+pub use crate::reader::ResourceReader;
+pub use crate::scanner::ResourceScanner;
+#[cfg(feature = "mmap")]
+pub use crate::mmap::MappedCarrier;
+pub use crate::core::{ResourceHead, ResourceEntry, CompressMode, AddOptions, VersionCompatibility};
+pub use crate::codec::Codec;
+pub use crate::error::AppenderError;
+pub use crate::crypto::{Cipher, Secret};
@@ -0,0 +1,135 @@
+use std::path::Path;
+use std::error::Error;
+use serde::Serialize;
+use crate::core::{CompressMode, ResourceEntry};
+use crate::carrier::Carrier;
+
+/// 资源信息(用于列出资源)
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ResourceInfo {
+    /// 资源ID
+    pub Id: String,
+    /// 资源文件名
+    pub Name: String,
+    /// 资源长度(写入载体的字节数)
+    pub Length: u64,
+    /// 资源大小(原始文件的字节数)
+    pub Size: u64,
+    /// 压缩模式
+    pub Compress: CompressMode,
+    /// 资源版本
+    pub Version: String,
+    /// 资源文件头起始位置
+    pub Offset: u64,
+}
+
+impl From<&ResourceEntry> for ResourceInfo {
+    fn from(entry: &ResourceEntry) -> Self {
+        ResourceInfo {
+            Id: entry.head.getId().to_string(),
+            Name: entry.head.getName().to_string(),
+            Length: entry.head.getLength(),
+            Size: entry.head.getSize(),
+            Compress: entry.head.getCompressMode(),
+            Version: entry.head.getVersion().to_string(),
+            Offset: entry.offset,
+        }
+    }
+}
+
+/// 列出载体文件中的所有资源
+pub fn listResources(targetFilePath: &Path) -> Result<Vec<ResourceInfo>, Box<dyn Error>> {
+    Ok(Carrier::open(targetFilePath)?.entries()?.iter().map(ResourceInfo::from).collect())
+}
+
+/// 输出格式
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ListFormat {
+    /// 表格
+    Table,
+    /// JSON
+    Json,
+    /// CSV
+    Csv,
+}
+
+impl ListFormat {
+    /// 所有格式名称
+    pub const NAMES: [&'static str; 3] = ["table", "json", "csv"];
+
+    /// 由格式名称解析
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "table" => Some(ListFormat::Table),
+            "json" => Some(ListFormat::Json),
+            "csv" => Some(ListFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// 表头
+const COLUMNS: [&str; 7] = ["ID", "Name", "Length", "Size", "Compress", "Version", "Offset"];
+
+/// 将资源信息转换为各列文本
+fn toColumns(info: &ResourceInfo) -> [String; 7] {
+    [
+        info.Id.clone(),
+        info.Name.clone(),
+        info.Length.to_string(),
+        info.Size.to_string(),
+        info.Compress.name().to_string(),
+        info.Version.clone(),
+        info.Offset.to_string(),
+    ]
+}
+
+/// 格式化资源列表
+pub fn formatList(infos: &[ResourceInfo], format: ListFormat) -> String {
+    match format {
+        ListFormat::Table => formatTable(infos),
+        ListFormat::Json => serde_json::to_string_pretty(infos).unwrap(),
+        ListFormat::Csv => formatCsv(infos),
+    }
+}
+
+/// 格式化为表格(按列对齐)
+fn formatTable(infos: &[ResourceInfo]) -> String {
+    let rows: Vec<[String; 7]> = infos.iter().map(toColumns).collect();
+    let mut widths: Vec<usize> = COLUMNS.iter().map(|column| column.chars().count()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let formatRow = |cells: Vec<&str>| -> String {
+        let line: Vec<String> = cells.iter().zip(widths.iter())
+            .map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - cell.chars().count())))
+            .collect();
+        line.join("  ").trim_end().to_string()
+    };
+
+    let mut lines = vec![formatRow(COLUMNS.to_vec())];
+    lines.extend(rows.iter().map(|row| formatRow(row.iter().map(|cell| cell.as_str()).collect())));
+    lines.join("\n")
+}
+
+/// 格式化为 CSV
+fn formatCsv(infos: &[ResourceInfo]) -> String {
+    // 包含分隔符、引号或换行的字段需要加引号
+    let escape = |cell: &str| -> String {
+        if cell.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", cell.replace('"', "\"\""))
+        } else {
+            cell.to_string()
+        }
+    };
+
+    let mut lines = vec![COLUMNS.join(",")];
+    for info in infos {
+        let row: Vec<String> = toColumns(info).iter().map(|cell| escape(cell)).collect();
+        lines.push(row.join(","));
+    }
+    lines.join("\n")
+}
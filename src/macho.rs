@@ -0,0 +1,160 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{Seek, SeekFrom};
+use crate::error::AppenderError;
+use crate::util::{readAt, readUInt};
+
+/// 32位 Mach-O 标识
+const MH_MAGIC: u32 = 0xFEED_FACE;
+
+/// 64位 Mach-O 标识
+const MH_MAGIC_64: u32 = 0xFEED_FACF;
+
+/// 通用二进制(Fat)标识(大端序)
+const FAT_MAGIC: u32 = 0xCAFE_BABE;
+
+/// 64位通用二进制(Fat)标识(大端序)
+const FAT_MAGIC_64: u32 = 0xCAFE_BABF;
+
+/// 通用二进制的最大架构数量(Java class 文件同样以 0xCAFEBABE 开头，其版本号不小于 45)
+const MAX_FAT_ARCH: u64 = 32;
+
+/// 段加载命令
+const LC_SEGMENT: u64 = 0x1;
+
+/// 64位段加载命令
+const LC_SEGMENT_64: u64 = 0x19;
+
+/// 代码签名加载命令(签名数据位于 __LINKEDIT 段末尾)
+const LC_CODE_SIGNATURE: u64 = 0x1D;
+
+/// Mach-O 架构映像(通用二进制中的一个架构)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MachOSlice {
+    /// 映像在文件中的起始位置
+    pub offset: u64,
+    /// 是否为 64位
+    pub is64: bool,
+    /// 是否为大端序
+    pub bigEndian: bool,
+    /// 映像结束位置(文件中的绝对位置)
+    pub end: u64,
+    /// 代码签名数据(文件中的绝对位置, 长度)，未签名时为 None
+    pub codeSignature: Option<(u64, u64)>,
+}
+
+/// Mach-O 文件映像信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct MachOImage {
+    /// 是否为通用二进制(Fat)
+    pub fat: bool,
+    /// 所有架构映像
+    pub slices: Vec<MachOSlice>,
+    /// 映像结束位置(附加数据起始位置)
+    pub imageEnd: u64,
+}
+
+impl MachOImage {
+    /// 解析 Mach-O 文件(包括通用二进制)的加载命令
+    /// # 返回值
+    /// 不是 Mach-O 文件时返回 None，文件头损坏时返回错误
+    pub fn parse(file: &mut File) -> Result<Option<Self>, Box<dyn Error>> {
+        let fileLength = file.seek(SeekFrom::End(0))?;
+        if fileLength < 8 {
+            return Ok(None);
+        }
+        let header = readAt(file, 0, 8)?;
+        let magic = readUInt(&header, 0, 4, true) as u32;
+
+        if magic == FAT_MAGIC || magic == FAT_MAGIC_64 {
+            let count = readUInt(&header, 4, 4, true);
+            if count == 0 || count > MAX_FAT_ARCH {
+                return Ok(None);
+            }
+            // fat_arch: cputype, cpusubtype, offset, size, align (fat_arch_64 的 offset、size 为 64位)
+            let entrySize = if magic == FAT_MAGIC_64 { 32 } else { 20 };
+            if 8 + entrySize * count > fileLength {
+                return Err(Box::from(AppenderError::Corrupt("The Mach-O fat header is out of range".to_string())));
+            }
+            let table = readAt(file, 8, (entrySize * count) as usize)?;
+            let mut slices = Vec::with_capacity(count as usize);
+            let mut imageEnd = 8 + entrySize * count;
+            for entry in table.chunks_exact(entrySize as usize) {
+                let (offset, size) = if magic == FAT_MAGIC_64 {
+                    (readUInt(entry, 8, 8, true), readUInt(entry, 16, 8, true))
+                } else {
+                    (readUInt(entry, 8, 4, true), readUInt(entry, 12, 4, true))
+                };
+                let slice = parseSlice(file, offset, fileLength)?
+                    .ok_or_else(|| AppenderError::InvalidHeader("Invalid Mach-O fat architecture".to_string()))?;
+                imageEnd = imageEnd.max(offset.saturating_add(size)).max(slice.end);
+                slices.push(slice);
+            }
+            return Ok(Some(MachOImage { fat: true, slices, imageEnd: imageEnd.min(fileLength) }));
+        }
+
+        Ok(parseSlice(file, 0, fileLength)?.map(|slice| MachOImage { fat: false, imageEnd: slice.end, slices: vec![slice] }))
+    }
+
+    /// 是否带有代码签名(任意架构)
+    pub fn isSigned(&self) -> bool {
+        self.slices.iter().any(|slice| slice.codeSignature.is_some())
+    }
+}
+
+/// 解析单个架构映像
+fn parseSlice(file: &mut File, offset: u64, fileLength: u64) -> Result<Option<MachOSlice>, Box<dyn Error>> {
+    if offset.saturating_add(28) > fileLength {
+        return Ok(None);
+    }
+    let header = readAt(file, offset, 28)?;
+    let (is64, bigEndian) = match (readUInt(&header, 0, 4, false) as u32, readUInt(&header, 0, 4, true) as u32) {
+        (MH_MAGIC, _) => (false, false),
+        (MH_MAGIC_64, _) => (true, false),
+        (_, MH_MAGIC) => (false, true),
+        (_, MH_MAGIC_64) => (true, true),
+        _ => return Ok(None),
+    };
+    let ncmds = readUInt(&header, 16, 4, bigEndian);
+    let sizeofcmds = readUInt(&header, 20, 4, bigEndian);
+    let headerSize = if is64 { 32 } else { 28 };
+    if offset + headerSize + sizeofcmds > fileLength {
+        return Err(Box::from(AppenderError::Corrupt("The Mach-O load commands are out of range".to_string())));
+    }
+
+    let commands = readAt(file, offset + headerSize, sizeofcmds as usize)?;
+    let mut end = headerSize + sizeofcmds;
+    let mut codeSignature = None;
+    let mut position = 0usize;
+    for _ in 0..ncmds {
+        if position + 8 > commands.len() {
+            return Err(Box::from(AppenderError::InvalidHeader("Invalid Mach-O load command".to_string())));
+        }
+        let cmd = readUInt(&commands, position, 4, bigEndian);
+        let cmdsize = readUInt(&commands, position + 4, 4, bigEndian) as usize;
+        if cmdsize < 8 || position + cmdsize > commands.len() {
+            return Err(Box::from(AppenderError::InvalidHeader("Invalid Mach-O load command".to_string())));
+        }
+        let command = &commands[position..position + cmdsize];
+        match cmd {
+            // segment_command: fileoff、filesize 位于段名及内存地址之后
+            LC_SEGMENT if cmdsize >= 56 => {
+                end = end.max(readUInt(command, 32, 4, bigEndian) + readUInt(command, 36, 4, bigEndian));
+            }
+            LC_SEGMENT_64 if cmdsize >= 72 => {
+                end = end.max(readUInt(command, 40, 8, bigEndian).saturating_add(readUInt(command, 48, 8, bigEndian)));
+            }
+            // linkedit_data_command: dataoff, datasize
+            LC_CODE_SIGNATURE if cmdsize >= 16 => {
+                let dataOffset = readUInt(command, 8, 4, bigEndian);
+                let dataSize = readUInt(command, 12, 4, bigEndian);
+                end = end.max(dataOffset + dataSize);
+                codeSignature = Some((offset + dataOffset, dataSize));
+            }
+            _ => {}
+        }
+        position += cmdsize;
+    }
+
+    Ok(Some(MachOSlice { offset, is64, bigEndian, end: offset.saturating_add(end).min(fileLength), codeSignature }))
+}
@@ -1,28 +1,28 @@
 // 禁用变量命名警告
 #![allow(non_snake_case)]
-// 运行使用基准测试
-#![feature(test)]
-
-
-#[macro_use]
-extern crate lazy_static;
 
 mod validator;
-mod util;
-mod core;
-
-#[cfg(test)]
-mod tests;
 
 use std::path::{PathBuf, Path};
-use clap::{Arg, SubCommand, AppSettings, App};
-use crate::validator::is_valid_path;
-use crate::core::{addResource, exportResource};
+use std::process;
+use std::fs::OpenOptions;
+use std::error::Error;
+use std::io::{self, Write};
+use clap::{Arg, ArgMatches, SubCommand, AppSettings, App};
+use crate::validator::{is_valid_path, is_valid_input};
+use Appender::core::{addResourceWith, addResourceFrom, addDirectory, exportResourceWith, exportResourceTo, versionCompatibility, AddOptions, CompressMode, VersionCompatibility};
+use Appender::list::{listResources, formatList, ListFormat};
+use Appender::pe::{PeImage, SignatureMode, computeChecksum};
+use Appender::format::{ImageInfo, CarrierFormat};
+use Appender::{Carrier, ConflictPolicy, Cipher, Secret, AppenderError, crypto};
+use Appender::error::{exitCode, EXIT_INVALID_ARGUMENT, EXIT_CORRUPT};
 
 
 fn main() {
     let matches = App::new(clap::crate_name!())
         .setting(AppSettings::ArgRequiredElseHelp)
+        // 参数包含无效的 UTF-8 时报错，而不是读取参数时 panic
+        .global_setting(AppSettings::StrictUtf8)
         .version(clap::crate_version!())
         .subcommands(vec![
             // 增加
@@ -34,13 +34,13 @@ fn main() {
                     .validator(is_valid_path)
                     .index(1))
                 .arg(Arg::with_name("Resources")
-                    .help("Resources")
+                    .help("Resources (\"-\" reads from standard input)")
                     .required(true)
-                    .validator(is_valid_path)
+                    .validator(is_valid_input)
                     .index(2))
                 .arg(Arg::with_name("id")
-                    .help("Resources ID")
-                    .required(true)
+                    .help("Resources ID (the id prefix with --recursive)")
+                    .required_unless("recursive")
                     .index(3))
                 .arg(Arg::with_name("newFilePath")
                     .help("new file path")
@@ -50,8 +50,41 @@ fn main() {
                     .long("compression")
                     .value_name("compression")
                     .default_value("1")
-                    .help("compression grade(0-9)")
-                ),
+                    .help("compression grade(gzip/xz: 0-9, zstd: 1-22)")
+                )
+                .arg(Arg::with_name("codec")
+                    .long("codec")
+                    .value_name("codec")
+                    .possible_values(&CompressMode::NAMES)
+                    .default_value("gzip")
+                    .help("compression codec")
+                )
+                .arg(Arg::with_name("replace")
+                    .short("r")
+                    .long("replace")
+                    .help("replace the resource with the same id")
+                )
+                .arg(Arg::with_name("signature")
+                    .long("signature")
+                    .value_name("signature")
+                    .possible_values(&SignatureMode::NAMES)
+                    .default_value("embed")
+                    .help("how to handle a signed target file (embed: keep the signature valid, append: invalidate the signature, refuse: do not modify)")
+                )
+                .arg(recursiveArg("add every file under the resources directory, the id is the prefix followed by the relative path"))
+                .arg(Arg::with_name("name")
+                    .long("name")
+                    .value_name("name")
+                    .help("resource file name (defaults to the file name of the resource, or the id when reading from standard input)")
+                )
+                .arg(Arg::with_name("align")
+                    .long("align")
+                    .value_name("bytes")
+                    .validator(|value| value.parse::<u64>().map(|_| ()).map_err(|e| e.to_string()))
+                    .help("align the resource data to a multiple of the given bytes (a power of two, e.g. 4096 for a page boundary)")
+                )
+                .arg(noChecksumArg())
+                .args(&secretArgs()),
             // 释放资源
             SubCommand::with_name("export")
                 .about("export Resources")
@@ -61,42 +94,490 @@ fn main() {
                     .validator(is_valid_path)
                     .index(1))
                 .arg(Arg::with_name("id")
-                    .help("Resources ID")
+                    .help("Resources ID (the id prefix with --recursive, the output directory with --all)")
                     .required(true)
                     .index(2))
                 .arg(Arg::with_name("outputPath")
-                    .help("outputPath")
+                    .help("outputPath (\"-\" writes to standard output)")
+                    .required_unless("all")
+                    .index(3))
+                .arg(recursiveArg("export every resource whose id starts with the given prefix, recreating the relative paths"))
+                .arg(Arg::with_name("all")
+                    .long("all")
+                    .conflicts_with_all(&["outputPath", "recursive"])
+                    .help("export every resource to the output directory using the stored file names")
+                )
+                .arg(Arg::with_name("onConflict")
+                    .long("on-conflict")
+                    .value_name("policy")
+                    .possible_values(&ConflictPolicy::NAMES)
+                    .default_value("overwrite")
+                    .help("what to do with --all or --recursive when an output file already exists or two resources have the same name")
+                )
+                .args(&secretArgs()),
+            // 删除资源
+            SubCommand::with_name("remove")
+                .about("Remove Resources")
+                .arg(Arg::with_name("TargetFile")
+                    .help("Target File Path")
                     .required(true)
-                    .index(3)),
+                    .validator(is_valid_path)
+                    .index(1))
+                .arg(Arg::with_name("id")
+                    .help("Resources ID")
+                    .required(true)
+                    .index(2))
+                .arg(noChecksumArg()),
+            // 校验资源
+            SubCommand::with_name("verify")
+                .about("Verify Resources")
+                .arg(Arg::with_name("TargetFile")
+                    .help("Target File Path")
+                    .required(true)
+                    .validator(is_valid_path)
+                    .index(1))
+                .arg(Arg::with_name("id")
+                    .help("Resources ID (all resources by default)")
+                    .multiple(true)
+                    .index(2))
+                .args(&secretArgs()),
+            // 列出资源
+            SubCommand::with_name("list")
+                .about("List Resources")
+                .arg(Arg::with_name("TargetFile")
+                    .help("Target File Path")
+                    .required(true)
+                    .validator(is_valid_path)
+                    .index(1))
+                .arg(Arg::with_name("format")
+                    .short("f")
+                    .long("format")
+                    .value_name("format")
+                    .possible_values(&ListFormat::NAMES)
+                    .default_value("table")
+                    .help("output format")
+                ),
+            // 迁移资源
+            SubCommand::with_name("migrate")
+                .about("Rewrite resources written by older versions in the current format")
+                .arg(Arg::with_name("TargetFile")
+                    .help("Target File Path")
+                    .required(true)
+                    .validator(is_valid_path)
+                    .index(1))
+                .arg(noChecksumArg()),
+            // PE 校验和
+            SubCommand::with_name("checksum")
+                .about("Recompute the PE checksum")
+                .arg(Arg::with_name("TargetFile")
+                    .help("Target File Path")
+                    .required(true)
+                    .validator(is_valid_path)
+                    .index(1))
+                .arg(Arg::with_name("verify")
+                    .long("verify")
+                    .help("only check whether the stored checksum is correct")
+                ),
         ])
-        .get_matches();
+        .get_matches_safe()
+        .unwrap_or_else(|e| {
+            // 帮助及版本信息正常退出，参数错误使用参数无效的退出码
+            if e.use_stderr() {
+                eprintln!("{}", e.message);
+                process::exit(EXIT_INVALID_ARGUMENT);
+            }
+            e.exit()
+        });
 
     // 增加资源
     if let Some(matches) = matches.subcommand_matches("add") {
         let targerFile = PathBuf::from(matches.value_of("TargetFile").unwrap());
         let resources = PathBuf::from(matches.value_of("Resources").unwrap());
-        let id = matches.value_of("id").unwrap();
+        let id = matches.value_of("id").unwrap_or("");
         let outputFile = matches.value_of("newFilePath").map(|path| Path::new(path));
-        let compressionGrade = matches.value_of("compression").map(|grade| grade.parse::<u32>().unwrap());
+        let options = match addOptions(matches) {
+            Ok(options) => options,
+            Err(e) => fail("Resource increase failed", e),
+        };
+        reportSignature(&targerFile, options.signature);
 
-        println!("Adding \"{}\" resources id \"{}\" to \"{}\"......", resources.to_str().unwrap(), id, targerFile.to_str().unwrap());
-        if let Err(e) = addResource(&*targerFile, &*resources, id, compressionGrade, outputFile) {
-            println!("Resource increase failed: {}", e);
+        println!("Adding \"{}\" resources id \"{}\" to \"{}\"......", resources.display(), id, targerFile.display());
+        reportForeignOverlay(&targerFile);
+        if matches.is_present("recursive") {
+            match addDirectory(&targerFile, &resources, id, &options, outputFile) {
+                Ok(ids) => println!("{} resources increase successfully", ids.len()),
+                Err(e) => fail("Resource increase failed", e),
+            }
             return;
         }
+        let result = if resources == Path::new("-") {
+            addResourceFrom(&targerFile, &mut io::stdin().lock(), id, &options, outputFile)
+        } else {
+            addResourceWith(&targerFile, &resources, id, &options, outputFile)
+        };
+        if let Err(e) = result {
+            fail("Resource increase failed", e);
+        }
         println!("Resources increase successfully");
     }
 
     // 释放资源
     if let Some(matches) = matches.subcommand_matches("export") {
         let targetFile = PathBuf::from(matches.value_of("TargetFile").unwrap());
-        let id = matches.value_of("id").unwrap();
-        let outputPath = PathBuf::from(matches.value_of("outputPath").unwrap());
-        println!("export resources id \"{}\" from \"{}\" to \"{}\"", id, targetFile.to_str().unwrap(), outputPath.to_str().unwrap());
-        if let Err(e) = exportResource(&*targetFile, id, &*outputPath) {
-            println!("Resource export failed: {}", e);
+        let all = matches.is_present("all");
+        let recursive = matches.is_present("recursive");
+        // 释放所有资源时第二个参数为输出目录
+        let (id, outputPath) = if all {
+            ("", PathBuf::from(matches.value_of("id").unwrap()))
+        } else {
+            (matches.value_of("id").unwrap(), PathBuf::from(matches.value_of("outputPath").unwrap()))
+        };
+        // 输出到标准输出时，提示信息输出到标准错误
+        let toStdout = outputPath == Path::new("-");
+        let report = |message: String| if toStdout { eprintln!("{}", message) } else { println!("{}", message) };
+        if all {
+            report(format!("export all resources from \"{}\" to \"{}\"", targetFile.display(), outputPath.display()));
+        } else {
+            report(format!("export resources id \"{}\" from \"{}\" to \"{}\"", id, targetFile.display(), outputPath.display()));
+        }
+        newerVersionWarnings(&targetFile).into_iter().for_each(&report);
+        let secret = match readSecret(matches) {
+            // 资源加密且未提供密钥时询问密码
+            Ok(None) if crypto::supported() && isEncrypted(&targetFile, if all || recursive { None } else { Some(id) }) => Some(promptPassword()),
+            Ok(secret) => secret,
+            Err(e) => {
+                report(format!("Resource export failed: {}", e));
+                exitWith(&*e);
+            }
+        };
+        if (all || recursive) && toStdout {
+            report("Resource export failed: multiple resources cannot be written to standard output".to_string());
+            process::exit(EXIT_INVALID_ARGUMENT);
+        }
+        if all || recursive {
+            let policy = ConflictPolicy::from_name(matches.value_of("onConflict").unwrap()).unwrap();
+            let result = Carrier::open(&targetFile).and_then(|mut carrier| {
+                carrier.set_secret(secret);
+                carrier.export_directory_report(id, &outputPath, policy)
+            });
+            match result {
+                Ok(report) => {
+                    for (id, path) in &report.skipped {
+                        println!("Skipped \"{}\": \"{}\" already exists (--on-conflict skip)", id, path.display());
+                    }
+                    for (id, path) in &report.overwritten {
+                        println!("Overwritten \"{}\": \"{}\" is written by a later resource with the same file name (--on-conflict overwrite)", id, path.display());
+                    }
+                    let dropped = report.skipped.len() + report.overwritten.len();
+                    if dropped > 0 {
+                        println!("{} resources export successfully, {} not exported", report.exported.len(), dropped);
+                    } else {
+                        println!("{} resources export successfully", report.exported.len());
+                    }
+                }
+                Err(e) => fail("Resource export failed", e),
+            }
             return;
         }
-        println!("Resource export successfully");
+        let result = if toStdout {
+            exportResourceTo(&targetFile, id, &mut io::stdout().lock(), secret).and_then(|_| Ok(io::stdout().flush()?))
+        } else {
+            exportResourceWith(&targetFile, id, &outputPath, secret)
+        };
+        if let Err(e) = result {
+            report(format!("Resource export failed: {}", e));
+            // 输出到管道时，后续程序需要知道输出不完整
+            exitWith(&*e);
+        }
+        report("Resource export successfully".to_string());
+    }
+
+    // 删除资源
+    if let Some(matches) = matches.subcommand_matches("remove") {
+        let targetFile = PathBuf::from(matches.value_of("TargetFile").unwrap());
+        let id = matches.value_of("id").unwrap();
+        println!("Removing resources id \"{}\" from \"{}\"......", id, targetFile.display());
+        let result = Carrier::open(&targetFile).and_then(|mut carrier| {
+            carrier.set_keep_checksum(matches.is_present("noChecksum"));
+            carrier.remove(id)
+        });
+        if let Err(e) = result {
+            fail("Resource remove failed", e);
+        }
+        println!("Resource remove successfully");
     }
+
+    // 校验资源
+    if let Some(matches) = matches.subcommand_matches("verify") {
+        let targetFile = PathBuf::from(matches.value_of("TargetFile").unwrap());
+        let ids: Option<Vec<&str>> = matches.values_of("id").map(|ids| ids.collect());
+        let secret = match readSecret(matches) {
+            Ok(None) if crypto::supported() && isEncrypted(&targetFile, None) => Some(promptPassword()),
+            Ok(secret) => secret,
+            Err(e) => fail("Resource verify failed", e),
+        };
+        let code = verifyResources(&targetFile, ids, secret);
+        if code != 0 {
+            process::exit(code);
+        }
+    }
+
+    // 迁移资源
+    if let Some(matches) = matches.subcommand_matches("migrate") {
+        let targetFile = PathBuf::from(matches.value_of("TargetFile").unwrap());
+        println!("Migrating resources in \"{}\"......", targetFile.display());
+        let result = Carrier::open(&targetFile).and_then(|mut carrier| {
+            carrier.set_keep_checksum(matches.is_present("noChecksum"));
+            carrier.migrate()
+        });
+        match result {
+            Ok(ids) if ids.is_empty() => println!("All resources are already in the current format"),
+            Ok(ids) => println!("{} resources migrate successfully", ids.len()),
+            Err(e) => fail("Resource migrate failed", e),
+        }
+    }
+
+    // PE 校验和
+    if let Some(matches) = matches.subcommand_matches("checksum") {
+        let targetFile = PathBuf::from(matches.value_of("TargetFile").unwrap());
+        let code = checksum(&targetFile, matches.is_present("verify"));
+        if code != 0 {
+            process::exit(code);
+        }
+    }
+
+    // 列出资源
+    if let Some(matches) = matches.subcommand_matches("list") {
+        let targetFile = PathBuf::from(matches.value_of("TargetFile").unwrap());
+        let format = ListFormat::from_name(matches.value_of("format").unwrap()).unwrap();
+        match listResources(&targetFile) {
+            Ok(infos) => {
+                // JSON、CSV 输出到标准输出时不混入其他内容
+                let info = imageInfo(&targetFile);
+                if format == ListFormat::Table {
+                    println!("Format: {}", info.format);
+                    newerVersionWarnings(&targetFile).iter().for_each(|warning| println!("{}", warning));
+                } else {
+                    eprintln!("Format: {}", info.format);
+                    newerVersionWarnings(&targetFile).iter().for_each(|warning| eprintln!("{}", warning));
+                }
+                println!("{}", formatList(&infos, format));
+            }
+            Err(e) => fail("Resource list failed", e),
+        }
+    }
+}
+
+/// 校验资源并输出每个资源的校验结果
+/// # 返回值
+/// 退出码: 全部校验通过时为 0，否则为第一个失败的资源的错误对应的退出码
+fn verifyResources(targetFile: &Path, ids: Option<Vec<&str>>, secret: Option<Secret>) -> i32 {
+    let mut carrier = match Carrier::open(targetFile) {
+        Ok(carrier) => carrier,
+        Err(e) => {
+            println!("Resource verify failed: {}", e);
+            return exitCode(&*e);
+        }
+    };
+    carrier.set_secret(secret);
+    println!("Format: {}", carrier.image_info().format);
+    reportForeignOverlay(targetFile);
+    newerVersionWarnings(targetFile).iter().for_each(|warning| println!("{}", warning));
+    let ids: Vec<String> = match ids {
+        Some(ids) => ids.iter().map(|id| id.to_string()).collect(),
+        None => match carrier.entries() {
+            Ok(entries) => entries.iter().map(|entry| entry.head.getId().to_string()).collect(),
+            Err(e) => {
+                println!("Resource verify failed: {}", e);
+                return exitCode(&*e);
+            }
+        },
+    };
+
+    let mut failed = 0;
+    let mut code = 0;
+    for id in &ids {
+        match carrier.verify(id) {
+            Ok(size) => println!("OK      \"{}\" ({} bytes)", id, size),
+            Err(e) => {
+                failed += 1;
+                println!("FAILED  \"{}\": {}", id, e);
+                if code == 0 {
+                    code = exitCode(&*e);
+                }
+            }
+        }
+    }
+    println!("{} resources verified, {} failed", ids.len(), failed);
+    code
+}
+
+/// 输出错误信息并以错误对应的退出码退出
+fn fail(message: &str, error: Box<dyn Error>) -> ! {
+    println!("{}: {}", message, error);
+    exitWith(&*error)
+}
+
+/// 以错误对应的退出码退出
+fn exitWith(error: &(dyn Error + 'static)) -> ! {
+    process::exit(exitCode(error))
+}
+
+/// 读取命令行中的增加资源选项
+fn addOptions(matches: &ArgMatches) -> Result<AddOptions, Box<dyn Error>> {
+    let compression = matches.value_of("compression").unwrap_or_default();
+    let compressionGrade = compression.parse::<u32>().map_err(|_| AppenderError::InvalidArgument(format!("Invalid compression grade \"{}\"", compression)))?;
+    let alignment = match matches.value_of("align") {
+        Some(align) => align.parse::<u64>().map_err(|_| AppenderError::InvalidArgument(format!("Invalid alignment \"{}\"", align)))?,
+        None => 0,
+    };
+    Ok(AddOptions {
+        compressMode: matches.value_of("codec").and_then(CompressMode::from_name).unwrap_or_default(),
+        compressionGrade,
+        replace: matches.is_present("replace"),
+        secret: readSecret(matches)?,
+        signature: matches.value_of("signature").and_then(SignatureMode::from_name).unwrap_or_default(),
+        keepChecksum: matches.is_present("noChecksum"),
+        name: matches.value_of("name").map(|name| name.to_string()),
+        alignment,
+    })
+}
+
+/// 提示目标文件中已存在的其他附加数据
+fn reportForeignOverlay(targetFile: &Path) {
+    if let Ok(Some((offset, length))) = Carrier::open(targetFile).and_then(|mut carrier| carrier.foreign_overlay()) {
+        println!("Note: \"{}\" already has {} bytes of foreign overlay data at offset {}", targetFile.display(), length, offset);
+    }
+}
+
+/// 新版本资源的警告信息(主版本相同的新版本可以读取，但新增的信息会被忽略)
+fn newerVersionWarnings(targetFile: &Path) -> Vec<String> {
+    let mut carrier = match Carrier::open(targetFile) {
+        Ok(carrier) => carrier,
+        Err(_e) => return Vec::new(),
+    };
+    let entries = carrier.entries().map(|entries| entries.to_vec()).unwrap_or_default();
+    entries.iter().filter(|entry| matches!(versionCompatibility(entry.head.getVersion()), Ok(VersionCompatibility::NewerMinor)))
+        .map(|entry| format!("Warning: resource \"{}\" was written by a newer version ({}), information added by that version is ignored, please consider upgrading the program", entry.head.getId(), entry.head.getVersion()))
+        .collect()
+}
+
+/// 校验或重新计算 PE 校验和
+/// # 返回值
+/// 退出码: 成功时为 0，校验和错误时为资源损坏的退出码，否则为错误对应的退出码
+fn checksum(targetFile: &Path, verify: bool) -> i32 {
+    let result = OpenOptions::new().read(true).write(!verify).open(targetFile).map_err(Box::<dyn Error>::from).and_then(|mut file| {
+        let image = PeImage::parse(&mut file)?.ok_or_else(|| AppenderError::InvalidArgument("Not a PE file".to_string()))?;
+        let stored = image.readChecksum(&mut file)?;
+        let computed = if verify { computeChecksum(&mut file, image.checksumOffset())? } else { image.updateChecksum(&mut file)? };
+        Ok((stored, computed))
+    });
+    match result {
+        Ok((stored, computed)) if verify => {
+            if stored == computed {
+                println!("Checksum OK: 0x{:08X}", stored);
+            } else {
+                println!("Checksum mismatch: stored 0x{:08X}, computed 0x{:08X}", stored, computed);
+            }
+            if stored == computed { 0 } else { EXIT_CORRUPT }
+        }
+        Ok((stored, computed)) => {
+            println!("Checksum updated: 0x{:08X} -> 0x{:08X}", stored, computed);
+            0
+        }
+        Err(e) => {
+            println!("Checksum failed: {}", e);
+            exitCode(&*e)
+        }
+    }
+}
+
+/// 识别目标文件格式
+fn imageInfo(targetFile: &Path) -> ImageInfo {
+    match Carrier::open(targetFile) {
+        Ok(mut carrier) => carrier.image_info(),
+        Err(_e) => ImageInfo::default(),
+    }
+}
+
+/// 提示增加资源会使签名失效
+fn reportSignature(targetFile: &Path, mode: SignatureMode) {
+    let info = imageInfo(targetFile);
+    if !info.signed || mode == SignatureMode::Refuse {
+        return;
+    }
+    match info.format {
+        // PE 文件嵌入模式下签名保持有效
+        CarrierFormat::Pe if mode == SignatureMode::Append => println!("Warning: \"{}\" is signed, appending resources will invalidate the signature", targetFile.display()),
+        CarrierFormat::MachO | CarrierFormat::FatMachO => println!("Warning: \"{}\" has a code signature (LC_CODE_SIGNATURE), appending resources will invalidate it, please sign it again", targetFile.display()),
+        _ => {}
+    }
+}
+
+/// 递归处理目录的参数
+fn recursiveArg<'a, 'b>(help: &'b str) -> Arg<'a, 'b> {
+    Arg::with_name("recursive")
+        .long("recursive")
+        .help(help)
+}
+
+/// 不重新计算 PE 校验和参数
+fn noChecksumArg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("noChecksum")
+        .long("no-checksum")
+        .help("keep the PE checksum instead of recomputing it")
+}
+
+/// 加密相关参数(密码、密钥文件，不支持加密时隐藏)
+fn secretArgs<'a, 'b>() -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("password")
+            .short("p")
+            .long("password")
+            .value_name("password")
+            .conflicts_with("keyFile")
+            .hidden(!crypto::supported())
+            .help("encrypt/decrypt the resource with a password"),
+        Arg::with_name("keyFile")
+            .short("k")
+            .long("key-file")
+            .value_name("keyFile")
+            .validator(is_valid_path)
+            .hidden(!crypto::supported())
+            .help("encrypt/decrypt the resource with a 32 bytes key file"),
+    ]
+}
+
+/// 读取命令行中的密钥来源
+fn readSecret(matches: &ArgMatches) -> Result<Option<Secret>, Box<dyn Error>> {
+    if let Some(password) = matches.value_of("password") {
+        return Ok(Some(Secret::Password(password.to_string())));
+    }
+    if let Some(keyFile) = matches.value_of("keyFile") {
+        return Ok(Some(Secret::from_key_file(Path::new(keyFile))?));
+    }
+    Ok(None)
+}
+
+/// 判断资源是否加密(未指定资源ID时判断是否有任意资源加密)
+fn isEncrypted(targetFile: &Path, id: Option<&str>) -> bool {
+    let mut carrier = match Carrier::open(targetFile) {
+        Ok(carrier) => carrier,
+        Err(_e) => return false,
+    };
+    match carrier.entries() {
+        Ok(entries) => entries.iter()
+            .filter(|entry| id.is_none_or(|id| entry.head.getId() == id))
+            .any(|entry| entry.head.getCipher() != Cipher::None),
+        Err(_e) => false,
+    }
+}
+
+/// 询问密码
+fn promptPassword() -> Secret {
+    eprint!("The resource is encrypted, please enter the password: ");
+    io::stderr().flush().ok();
+    let mut password = String::new();
+    io::stdin().read_line(&mut password).ok();
+    Secret::Password(password.trim_end_matches(['\r', '\n']).to_string())
 }
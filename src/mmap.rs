@@ -0,0 +1,64 @@
+use std::error::Error;
+use std::io::{self, Cursor, Write};
+use memmap2::Mmap;
+use crate::core::{CompressMode, ResourceEntry, resourceDecoder};
+use crate::crypto::Cipher;
+use crate::util::DigestWriter;
+use crate::error::AppenderError;
+
+/// 内存映射的载体文件(零拷贝读取未压缩且未加密的资源)
+///
+/// 映射期间载体文件不应被修改(包括通过 [`crate::Carrier`] 增加、删除资源)，否则读取到的数据不确定
+pub struct MappedCarrier {
+    /// 载体文件的内存映射
+    map: Mmap,
+    /// 资源条目
+    entries: Vec<ResourceEntry>,
+}
+
+impl MappedCarrier {
+    /// 创建内存映射的载体文件
+    /// # 参数
+    /// 1. 载体文件的内存映射
+    /// 2. 资源条目
+    pub fn new(map: Mmap, entries: Vec<ResourceEntry>) -> Self {
+        MappedCarrier { map, entries }
+    }
+
+    /// 资源条目
+    pub fn entries(&self) -> &[ResourceEntry] {
+        &self.entries
+    }
+
+    /// 查找资源条目
+    fn find(&self, id: &str) -> Result<&ResourceEntry, Box<dyn Error>> {
+        self.entries.iter().find(|entry| entry.head.getId().eq(id)).ok_or_else(|| Box::from(AppenderError::NotFound("Resource not found".to_string())))
+    }
+
+    /// 获取资源数据(借用映射的载体文件，不复制数据)
+    ///
+    /// 只支持未压缩且未加密的资源，检查版本及尾部标识，不检查摘要(见 [`MappedCarrier::verify`])
+    pub fn get(&self, id: &str) -> Result<&[u8], Box<dyn Error>> {
+        let entry = self.find(id)?;
+        if entry.head.getCompressMode() != CompressMode::None || entry.head.getCipher() != Cipher::None {
+            return Err(Box::from(AppenderError::InvalidArgument("Only uncompressed and unencrypted resources can be mapped, please use open_resource instead".to_string())));
+        }
+        if entry.head.getLength() != entry.head.getSize() || entry.nextOffset() > self.map.len() as u64 {
+            return Err(Box::from(AppenderError::Corrupt("The resource to be exported is incomplete".to_string())));
+        }
+        resourceDecoder(Cursor::new(&self.map[..]), entry, None)?;
+        Ok(&self.map[entry.dataOffset() as usize..entry.endOffset() as usize])
+    }
+
+    /// 校验资源摘要(1.0.0 版本资源没有摘要，只检查是否完整)
+    pub fn verify(&self, id: &str) -> Result<(), Box<dyn Error>> {
+        let data = self.get(id)?;
+        let entry = self.find(id)?;
+        let mut digest = DigestWriter::new(io::sink());
+        digest.write_all(data)?;
+        if !entry.head.getDigest().is_empty() && digest.finalize() != entry.head.getDigest() {
+            return Err(Box::from(AppenderError::Corrupt("The resource is corrupted, the checksum does not match".to_string())));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,340 @@
+use std::error::Error;
+use std::convert::TryInto;
+use std::fs::File;
+use std::io::{Read, Write, SeekFrom, Seek};
+use crate::core::BUFFER_SIZE;
+use crate::error::AppenderError;
+use crate::util::readAt;
+
+/// DOS 头标识 "MZ"
+const DOS_SIGNATURE: [u8; 2] = [0x4D, 0x5A];
+
+/// NT 头标识 "PE\0\0"
+const NT_SIGNATURE: [u8; 4] = [0x50, 0x45, 0x00, 0x00];
+
+/// PE32 可选头标识
+const PE32_MAGIC: u16 = 0x10B;
+
+/// PE32+ 可选头标识
+const PE32_PLUS_MAGIC: u16 = 0x20B;
+
+/// COFF 文件头长度
+const FILE_HEADER_LENGTH: u64 = 20;
+
+/// 节表项长度
+const SECTION_HEADER_LENGTH: u64 = 40;
+
+/// 数据目录项长度
+const DATA_DIRECTORY_LENGTH: u64 = 8;
+
+/// 安全(证书)数据目录序号
+const SECURITY_DIRECTORY_INDEX: u32 = 4;
+
+/// 校验和字段相对可选头的位置(PE32 与 PE32+ 相同)
+const CHECKSUM_OFFSET: u64 = 64;
+
+/// WIN_CERTIFICATE 结构头长度(dwLength + wRevision + wCertificateType)
+const CERTIFICATE_HEADER_LENGTH: u64 = 8;
+
+/// 证书表对齐长度
+pub const CERTIFICATE_ALIGNMENT: u64 = 8;
+
+/// 数据目录(安全目录中的地址为文件偏移)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DataDirectory {
+    pub address: u32,
+    pub size: u32,
+}
+
+/// 节表项
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionHeader {
+    /// 节名称
+    pub name: String,
+    /// 节数据在文件中的长度
+    pub sizeOfRawData: u32,
+    /// 节数据在文件中的起始位置
+    pub pointerToRawData: u32,
+}
+
+impl SectionHeader {
+    /// 节数据在文件中的结束位置
+    pub fn rawEnd(&self) -> u64 {
+        self.pointerToRawData as u64 + self.sizeOfRawData as u64
+    }
+}
+
+/// PE 文件映像信息
+///
+/// 映像结束位置之后的数据即附加数据(Overlay)，不会被加载到内存
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeImage {
+    /// 是否为 PE32+(64位)
+    pub is64: bool,
+    /// NT 头起始位置
+    pub ntOffset: u64,
+    /// 可选头起始位置
+    pub optionalHeaderOffset: u64,
+    /// 所有头的长度(SizeOfHeaders)
+    pub sizeOfHeaders: u32,
+    /// 节表
+    pub sections: Vec<SectionHeader>,
+    /// 安全(证书)数据目录
+    pub securityDirectory: DataDirectory,
+    /// 安全数据目录项在文件中的位置(没有该数据目录时为 None)
+    pub securityDirectoryOffset: Option<u64>,
+    /// 映像结束位置(附加数据起始位置)
+    pub imageEnd: u64,
+}
+
+impl PeImage {
+    /// 解析 PE 文件头及节表
+    /// # 返回值
+    /// 不是 PE 文件时返回 None，PE 文件头损坏时返回错误
+    pub fn parse(file: &mut File) -> Result<Option<Self>, Box<dyn Error>> {
+        let fileLength = file.seek(SeekFrom::End(0))?;
+        if fileLength < 0x40 || readAt(file, 0, 2)? != DOS_SIGNATURE {
+            return Ok(None);
+        }
+        let ntOffset = readU32(file, 0x3C)? as u64;
+        if ntOffset + 4 + FILE_HEADER_LENGTH > fileLength || readAt(file, ntOffset, 4)? != NT_SIGNATURE {
+            return Ok(None);
+        }
+
+        // COFF 文件头
+        let fileHeaderOffset = ntOffset + 4;
+        let numberOfSections = readU16(file, fileHeaderOffset + 2)? as u64;
+        let sizeOfOptionalHeader = readU16(file, fileHeaderOffset + 16)? as u64;
+
+        // 可选头
+        let optionalHeaderOffset = fileHeaderOffset + FILE_HEADER_LENGTH;
+        let is64 = match readU16(file, optionalHeaderOffset)? {
+            PE32_MAGIC => false,
+            PE32_PLUS_MAGIC => true,
+            magic => return Err(Box::from(AppenderError::InvalidHeader(format!("Unknown PE optional header magic 0x{:X}", magic)))),
+        };
+        let sizeOfHeaders = readU32(file, optionalHeaderOffset + 60)?;
+        let (rvaAndSizesOffset, dataDirectoryOffset) = if is64 { (108, 112) } else { (92, 96) };
+        let numberOfRvaAndSizes = readU32(file, optionalHeaderOffset + rvaAndSizesOffset)?;
+
+        let mut securityDirectory = DataDirectory::default();
+        let mut securityDirectoryOffset = None;
+        let offset = optionalHeaderOffset + dataDirectoryOffset + SECURITY_DIRECTORY_INDEX as u64 * DATA_DIRECTORY_LENGTH;
+        if numberOfRvaAndSizes > SECURITY_DIRECTORY_INDEX && offset + DATA_DIRECTORY_LENGTH <= optionalHeaderOffset + sizeOfOptionalHeader {
+            securityDirectory = DataDirectory { address: readU32(file, offset)?, size: readU32(file, offset + 4)? };
+            securityDirectoryOffset = Some(offset);
+        }
+
+        // 节表
+        let sectionTableOffset = optionalHeaderOffset + sizeOfOptionalHeader;
+        if sectionTableOffset + numberOfSections * SECTION_HEADER_LENGTH > fileLength {
+            return Err(Box::from(AppenderError::Corrupt("The PE section table is out of range".to_string())));
+        }
+        let mut sections = Vec::with_capacity(numberOfSections as usize);
+        for i in 0..numberOfSections {
+            let data = readAt(file, sectionTableOffset + i * SECTION_HEADER_LENGTH, SECTION_HEADER_LENGTH as usize)?;
+            let name = String::from_utf8_lossy(&data[..8]).trim_end_matches('\0').to_string();
+            sections.push(SectionHeader {
+                name,
+                sizeOfRawData: u32::from_le_bytes(data[16..20].try_into().unwrap()),
+                pointerToRawData: u32::from_le_bytes(data[20..24].try_into().unwrap()),
+            });
+        }
+
+        // 映像结束位置: 文件头及所有节数据的结束位置(没有文件数据的节不计算在内)
+        let imageEnd = sections.iter()
+            .filter(|section| section.sizeOfRawData > 0)
+            .map(|section| section.rawEnd())
+            .fold(sizeOfHeaders as u64, u64::max);
+
+        Ok(Some(PeImage {
+            is64,
+            ntOffset,
+            optionalHeaderOffset,
+            sizeOfHeaders,
+            sections,
+            securityDirectory,
+            securityDirectoryOffset,
+            imageEnd: imageEnd.min(fileLength),
+        }))
+    }
+
+    /// 是否带有数字签名(Authenticode 证书表)
+    pub fn isSigned(&self) -> bool {
+        self.securityDirectory.address != 0 && self.securityDirectory.size != 0
+    }
+
+    /// 读取证书表
+    /// # 返回值
+    /// 没有数字签名时返回 None，证书表损坏时返回错误
+    pub fn certificateTable(&self, file: &mut File) -> Result<Option<CertificateTable>, Box<dyn Error>> {
+        let directoryOffset = match self.securityDirectoryOffset {
+            Some(offset) if self.isSigned() => offset,
+            _ => return Ok(None),
+        };
+        let address = self.securityDirectory.address as u64;
+        let end = address + self.securityDirectory.size as u64;
+        if end > file.seek(SeekFrom::End(0))? {
+            return Err(Box::from(AppenderError::Corrupt("The certificate table is out of range".to_string())));
+        }
+
+        // 遍历 WIN_CERTIFICATE 结构(每个结构按 8 字节对齐)，找到最后一个证书
+        let mut offset = address;
+        let mut lastOffset = address;
+        while offset + CERTIFICATE_HEADER_LENGTH <= end {
+            let length = readU32(file, offset)? as u64;
+            if length < CERTIFICATE_HEADER_LENGTH {
+                return Err(Box::from(AppenderError::InvalidHeader("Invalid certificate length".to_string())));
+            }
+            lastOffset = offset;
+            offset += length.div_ceil(CERTIFICATE_ALIGNMENT) * CERTIFICATE_ALIGNMENT;
+        }
+        Ok(Some(CertificateTable { address, size: end - address, lastOffset, directoryOffset }))
+    }
+
+    /// 校验和字段在文件中的位置
+    pub fn checksumOffset(&self) -> u64 {
+        self.optionalHeaderOffset + CHECKSUM_OFFSET
+    }
+
+    /// 读取文件头中的校验和
+    pub fn readChecksum(&self, file: &mut File) -> Result<u32, Box<dyn Error>> {
+        readU32(file, self.checksumOffset())
+    }
+
+    /// 重新计算校验和并写入文件头
+    /// # 返回值
+    /// 新的校验和
+    pub fn updateChecksum(&self, file: &mut File) -> Result<u32, Box<dyn Error>> {
+        let checksum = computeChecksum(file, self.checksumOffset())?;
+        writeU32(file, self.checksumOffset(), checksum)?;
+        Ok(checksum)
+    }
+}
+
+/// Authenticode 证书表(位于附加数据中，不参与签名摘要计算)
+#[derive(Debug, Clone, PartialEq)]
+pub struct CertificateTable {
+    /// 证书表起始位置
+    pub address: u64,
+    /// 证书表长度
+    pub size: u64,
+    /// 最后一个 WIN_CERTIFICATE 结构的起始位置
+    pub lastOffset: u64,
+    /// 安全数据目录项在文件中的位置
+    directoryOffset: u64,
+}
+
+impl CertificateTable {
+    /// 证书表结束位置
+    pub fn end(&self) -> u64 {
+        self.address + self.size
+    }
+
+    /// 调整证书表长度，使证书表(包括最后一个证书的填充)延伸到指定位置
+    ///
+    /// 同时修改安全数据目录及最后一个证书的 dwLength，签名工具会忽略证书数据之后的填充，因此签名仍然有效
+    /// # 参数
+    /// 1. 目标文件(可写)
+    /// 2. 证书表新的结束位置(必须 8 字节对齐)
+    pub fn resize(&mut self, file: &mut File, end: u64) -> Result<(), Box<dyn Error>> {
+        let size = end.checked_sub(self.address).filter(|size| *size % CERTIFICATE_ALIGNMENT == 0 && *size <= u32::MAX as u64)
+            .ok_or_else(|| AppenderError::Corrupt("Invalid certificate table size".to_string()))?;
+        let lastLength = end.checked_sub(self.lastOffset).filter(|length| *length >= CERTIFICATE_HEADER_LENGTH)
+            .ok_or_else(|| AppenderError::Corrupt("Invalid certificate table size".to_string()))?;
+        writeU32(file, self.directoryOffset + 4, size as u32)?;
+        writeU32(file, self.lastOffset, lastLength as u32)?;
+        self.size = size;
+        Ok(())
+    }
+}
+
+/// 处理数字签名的方式
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SignatureMode {
+    /// 将资源嵌入证书表的填充中，保持签名有效
+    #[default]
+    Embed,
+    /// 直接追加到文件末尾(签名将失效)
+    Append,
+    /// 目标文件带有数字签名时拒绝修改
+    Refuse,
+}
+
+impl SignatureMode {
+    /// 所有模式名称
+    pub const NAMES: [&'static str; 3] = ["embed", "append", "refuse"];
+
+    /// 由模式名称解析
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "embed" => Some(SignatureMode::Embed),
+            "append" => Some(SignatureMode::Append),
+            "refuse" => Some(SignatureMode::Refuse),
+            _ => None,
+        }
+    }
+}
+
+/// 计算 PE 校验和(与 CheckSumMappedFile 相同的算法)
+///
+/// 将文件按 16 位小端序求和(进位回卷，跳过校验和字段，奇数长度时末尾补 0)，再加上文件长度
+/// # 参数
+/// 1. 目标文件
+/// 2. 校验和字段在文件中的位置
+pub fn computeChecksum(file: &mut File, checksumOffset: u64) -> Result<u32, Box<dyn Error>> {
+    let fileLength = file.seek(SeekFrom::End(0))?;
+    file.seek(SeekFrom::Start(0))?;
+
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut position = 0u64;
+    let mut sum = 0u64;
+    // 上一个缓冲区剩余的奇数字节
+    let mut pending: Option<u8> = None;
+    loop {
+        let nbytes = file.read(&mut buffer)?;
+        if nbytes == 0 {
+            break;
+        }
+        let data = &mut buffer[..nbytes];
+        // 校验和字段按 0 计算
+        for i in checksumOffset..checksumOffset + 4 {
+            if i >= position && i < position + nbytes as u64 {
+                data[(i - position) as usize] = 0;
+            }
+        }
+        position += nbytes as u64;
+
+        let mut data = &data[..];
+        if let Some(low) = pending.take() {
+            sum += u16::from_le_bytes([low, data[0]]) as u64;
+            data = &data[1..];
+        }
+        let mut words = data.chunks_exact(2);
+        for word in &mut words {
+            sum += u16::from_le_bytes([word[0], word[1]]) as u64;
+            sum = (sum & 0xFFFF) + (sum >> 16);
+        }
+        pending = words.remainder().first().copied();
+    }
+    if let Some(low) = pending {
+        sum += low as u64;
+    }
+    sum = (sum & 0xFFFF) + (sum >> 16);
+    sum = (sum & 0xFFFF) + (sum >> 16);
+    Ok((sum as u32).wrapping_add(fileLength as u32))
+}
+
+fn readU16(file: &mut File, offset: u64) -> Result<u16, Box<dyn Error>> {
+    Ok(u16::from_le_bytes(readAt(file, offset, 2)?.try_into().unwrap()))
+}
+
+fn readU32(file: &mut File, offset: u64) -> Result<u32, Box<dyn Error>> {
+    Ok(u32::from_le_bytes(readAt(file, offset, 4)?.try_into().unwrap()))
+}
+
+fn writeU32(file: &mut File, offset: u64, value: u32) -> Result<(), Box<dyn Error>> {
+    file.seek(SeekFrom::Start(offset))?;
+    file.write_all(&value.to_le_bytes())?;
+    Ok(())
+}
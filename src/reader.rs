@@ -0,0 +1,159 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use crate::core::{CompressMode, ResourceEntry, resourceDecoder};
+use crate::crypto::{Cipher, Secret};
+use crate::util::DigestWriter;
+use crate::error::AppenderError;
+
+/// 资源读取器(读取解密、解压后的原始资源数据，不释放到磁盘)
+///
+/// 未压缩且未加密的资源直接定位到载体文件中的数据；其他资源按顺序解码，向后定位时从头重新解码。
+/// 从头顺序读取到末尾时会检查资源大小及摘要
+pub struct ResourceReader {
+    /// 载体文件(读取器独占)
+    file: File,
+    /// 资源条目
+    entry: ResourceEntry,
+    /// 加密密钥来源
+    secret: Option<Secret>,
+    /// 解码读取器(直接读取的资源为 None)
+    decoder: Option<Box<dyn Read>>,
+    /// 当前位置
+    position: u64,
+    /// 从头连续读取的数据摘要(跳过数据后为 None)
+    digest: Option<DigestWriter<io::Sink>>,
+}
+
+impl ResourceReader {
+    /// 创建资源读取器
+    /// # 参数
+    /// 1. 载体文件
+    /// 2. 资源条目
+    /// 3. 加密密钥来源(资源加密时必须提供)
+    pub fn new(file: File, entry: ResourceEntry, secret: Option<Secret>) -> Result<Self, Box<dyn Error>> {
+        let mut reader = ResourceReader { file, entry, secret, decoder: None, position: 0, digest: None };
+        reader.restart()?;
+        if !reader.entry.head.isSizeKnown() {
+            // 原始大小未知的旧版本压缩资源先解压一次得到大小
+            let decoder = reader.decoder.as_mut().unwrap();
+            let size = io::copy(decoder, &mut io::sink()).map_err(AppenderError::from_decode)?;
+            reader.entry.head.setSize(size);
+            reader.restart()?;
+        }
+        Ok(reader)
+    }
+
+    /// 原始资源大小
+    pub fn size(&self) -> u64 {
+        self.entry.head.getSize()
+    }
+
+    /// 资源条目
+    pub fn entry(&self) -> &ResourceEntry {
+        &self.entry
+    }
+
+    /// 是否可以直接读取载体文件中的数据(未压缩且未加密)
+    fn isStored(&self) -> bool {
+        self.entry.head.getCompressMode() == CompressMode::None && self.entry.head.getCipher() == Cipher::None
+    }
+
+    /// 回到资源起始位置(重新创建解码读取器)
+    ///
+    /// 第一次解密前由密码派生密钥并替换密码，向后定位时不会重复派生
+    fn restart(&mut self) -> Result<(), Box<dyn Error>> {
+        if let Some(secret) = self.secret.as_ref().filter(|secret| secret.needSalt() && self.entry.head.getCipher() != Cipher::None) {
+            self.secret = Some(secret.derive(self.entry.head.getSalt())?);
+        }
+        let file = self.file.try_clone()?;
+        let decoder = resourceDecoder(file, &self.entry, self.secret.as_ref())?;
+        self.decoder = if self.isStored() { None } else { Some(decoder) };
+        self.position = 0;
+        self.digest = Some(DigestWriter::new(io::sink()));
+        Ok(())
+    }
+
+    /// 读取到末尾时检查资源大小及摘要
+    fn finish(&mut self) -> io::Result<()> {
+        if self.position != self.size() {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "The resource is incomplete"));
+        }
+        let expected = self.entry.head.getDigest();
+        if let Some(digest) = self.digest.take() {
+            if !expected.is_empty() && digest.finalize() != expected {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "The resource is corrupted, the checksum does not match"));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Read for ResourceReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let nbytes = match self.decoder.as_mut() {
+            Some(decoder) => {
+                let nbytes = decoder.read(buf)?;
+                if self.position + nbytes as u64 > self.size() {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, "The resource is larger than recorded"));
+                }
+                nbytes
+            }
+            None => {
+                let remaining = self.size().saturating_sub(self.position);
+                let length = std::cmp::min(buf.len() as u64, remaining) as usize;
+                self.file.seek(SeekFrom::Start(self.entry.dataOffset() + self.position))?;
+                self.file.read(&mut buf[..length])?
+            }
+        };
+        if nbytes == 0 {
+            // 定位到末尾之后不再检查
+            if self.position <= self.size() {
+                self.finish()?;
+            }
+            return Ok(0);
+        }
+        if let Some(digest) = self.digest.as_mut() {
+            digest.write_all(&buf[..nbytes])?;
+        }
+        self.position += nbytes as u64;
+        Ok(nbytes)
+    }
+}
+
+impl Seek for ResourceReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.size().checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        }.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid seek to a negative or overflowing position"))?;
+
+        if target == self.position {
+            return Ok(target);
+        }
+        // 解码读取器只能向前读取，向后定位时从头重新解码
+        if self.decoder.is_some() && target < self.position {
+            self.restart().map_err(|e| io::Error::other(e.to_string()))?;
+        }
+        // 从头读取时重新计算摘要，跳过的数据不计算摘要
+        self.digest = if target == 0 { Some(DigestWriter::new(io::sink())) } else { None };
+        if self.decoder.is_none() {
+            self.position = target;
+            return Ok(target);
+        }
+
+        // 定位到末尾之后时解码读取器停留在末尾
+        let skip = std::cmp::min(target, self.size()) - std::cmp::min(self.position, self.size());
+        let decoder = self.decoder.as_mut().unwrap();
+        let skipped = io::copy(&mut decoder.take(skip), &mut io::sink())?;
+        if skipped != skip {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "The resource is incomplete"));
+        }
+        self.position = target;
+        Ok(target)
+    }
+}
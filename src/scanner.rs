@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use memchr::memmem;
+use crate::core::{ResourceEntry, readResourceHead, HEAD_MAGIC, HEAD_MAGIC_V1, END_IDENTIFIER, BUFFER_SIZE};
+use crate::error::isVersionMismatch;
+
+/// 相邻两块之间保留的重叠长度(最长标识长度 - 1)，跨越块边界的标识在下一块中也能完整找到
+const OVERLAP: usize = HEAD_MAGIC_V1.len() - 1;
+
+/// 资源扫描器(按位置顺序逐个返回文件中找到的资源条目)
+///
+/// 按块读取文件，在每块中查找所有的资源文件头标识，并与上一块末尾的 [`OVERLAP`] 字节拼接，跨越块边界的标识不会遗漏。
+/// 资源文件头起始位置 = 标识在文件中的位置 - 标识在文件头中的位置:
+/// - v2 文件头以标识开头，标识位置为 0
+/// - v1 文件头由 bincode 序列化，第一个字段为标识(`Vec<u8>`)，其前为 8 字节的 u64 长度，标识位置为 8
+///
+/// 标识也可能出现在资源数据中，无法解析为资源文件头的位置会被跳过；由新的主版本写入的资源文件头返回版本不兼容的错误。
+/// 找到完整的资源(尾部标识正确)后从资源结束位置继续扫描，资源数据中的文件头(如资源本身是载体文件)不会被当作资源。
+/// 解析文件头会移动文件位置，每次读取下一块前都会重新定位到扫描位置
+pub struct ResourceScanner<'a> {
+    /// 载体文件
+    file: &'a mut File,
+    /// 扫描起始位置(之前的资源文件头会被忽略)
+    start: u64,
+    /// 下一块的读取位置
+    position: u64,
+    /// (标识查找器, 标识在文件头中的位置)
+    finders: [(memmem::Finder<'static>, u64); 2],
+    /// 上一块末尾的重叠数据及当前块
+    window: Vec<u8>,
+    /// 当前块中尚未解析的资源文件头位置(按位置排序)
+    pending: VecDeque<u64>,
+    /// 文件是否读取完毕
+    finished: bool,
+}
+
+impl<'a> ResourceScanner<'a> {
+    /// 创建资源扫描器
+    /// # 参数
+    /// 1. 载体文件
+    /// 2. 扫描起始位置
+    pub fn new(file: &'a mut File, start: u64) -> Self {
+        ResourceScanner {
+            file,
+            start,
+            position: start,
+            finders: [(memmem::Finder::new(&HEAD_MAGIC), 0), (memmem::Finder::new(&HEAD_MAGIC_V1), 8)],
+            window: Vec::with_capacity(OVERLAP + BUFFER_SIZE),
+            pending: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    /// 读取下一块并记录其中所有资源文件头的位置
+    fn scanChunk(&mut self) -> Result<(), Box<dyn Error>> {
+        self.file.seek(SeekFrom::Start(self.position))?;
+        let overlap = self.window.len();
+        self.window.resize(overlap + BUFFER_SIZE, 0);
+        let mut nbytes = 0;
+        while nbytes < BUFFER_SIZE {
+            match self.file.read(&mut self.window[overlap + nbytes..])? {
+                0 => break,
+                size => nbytes += size,
+            }
+        }
+        self.window.truncate(overlap + nbytes);
+        // 窗口第一个字节在文件中的位置
+        let windowStart = self.position - overlap as u64;
+
+        let mut offsets = Vec::new();
+        for (finder, magicOffset) in &self.finders {
+            for index in finder.find_iter(&self.window) {
+                // 完全位于重叠部分的标识已在上一块中找到
+                if index + finder.needle().len() <= overlap {
+                    continue;
+                }
+                match (windowStart + index as u64).checked_sub(*magicOffset) {
+                    Some(offset) if offset >= self.start => offsets.push(offset),
+                    _ => {}
+                }
+            }
+        }
+        offsets.sort_unstable();
+        self.pending.extend(offsets);
+
+        self.position += nbytes as u64;
+        self.finished = nbytes < BUFFER_SIZE;
+        let keep = std::cmp::min(self.window.len(), OVERLAP);
+        self.window.drain(..self.window.len() - keep);
+        Ok(())
+    }
+
+    /// 跳过资源数据，从资源结束位置继续扫描
+    ///
+    /// 尾部标识不正确(资源不完整或长度有误)时不跳过，其后的资源仍能找到
+    fn skipEntry(&mut self, entry: &ResourceEntry) -> Result<(), Box<dyn Error>> {
+        let mut endBuffer = [0u8; END_IDENTIFIER.len()];
+        self.file.seek(SeekFrom::Start(entry.endOffset()))?;
+        if self.file.read_exact(&mut endBuffer).is_err() || endBuffer != END_IDENTIFIER {
+            return Ok(());
+        }
+        let nextOffset = entry.nextOffset();
+        self.pending.retain(|&offset| offset >= nextOffset);
+        if nextOffset > self.position {
+            // 重叠数据位于资源数据中，不再需要
+            self.position = nextOffset;
+            self.window.clear();
+            self.finished = false;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Iterator for ResourceScanner<'a> {
+    type Item = Result<ResourceEntry, Box<dyn Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(offset) = self.pending.pop_front() {
+                match readResourceHead(self.file, offset) {
+                    Ok((head, headLen)) => {
+                        let entry = ResourceEntry { offset, headLen, head };
+                        if let Err(e) = self.skipEntry(&entry) {
+                            self.finished = true;
+                            return Some(Err(e));
+                        }
+                        return Some(Ok(entry));
+                    }
+                    // 校验通过但由新的主版本写入的资源文件头
+                    Err(e) if isVersionMismatch(&*e) => return Some(Err(e)),
+                    // 不是资源文件头(资源数据中的相同字节)时继续查找
+                    Err(_e) => continue,
+                }
+            }
+            if self.finished {
+                return None;
+            }
+            if let Err(e) = self.scanChunk() {
+                self.finished = true;
+                return Some(Err(e));
+            }
+        }
+    }
+}
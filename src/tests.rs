@@ -1,4 +1,4 @@
-use crate::core::{addResource, exportResource, ResourceHead, CompressMode, findResourcesConfig};
+use crate::core::{addResource, exportResource, ResourceHead, CompressMode, findResourcesConfig, HEAD_MAGIC_V1};
 use std::path::{PathBuf};
 use std::{fs};
 
@@ -113,3 +113,1543 @@ fn test4(){
     });
     println!("============");
 }
+
+use crate::carrier::Carrier;
+
+/// 创建临时测试目录(包含一个目标文件与一个资源文件)
+fn tempTestPath(name: &str) -> PathBuf {
+    let testPath = std::env::temp_dir().join("Appender-test").join(name);
+    let _ = fs::remove_dir_all(&testPath);
+    fs::create_dir_all(&testPath).unwrap();
+    fs::write(testPath.join("target.exe"), b"MZ host executable").unwrap();
+    fs::write(testPath.join("source.txt"), "Appender ".repeat(1000)).unwrap();
+    testPath
+}
+
+/// 载体 增加、列出、读取资源测试
+#[test]
+fn carrierTest() {
+    let testPath = tempTestPath("carrier");
+    let mut carrier = Carrier::open(testPath.join("target.exe")).unwrap();
+    carrier.add(&testPath.join("source.txt"), "plain", None).unwrap();
+    carrier.add(&testPath.join("source.txt"), "gzip", Some(9)).unwrap();
+
+    let ids: Vec<String> = carrier.entries().unwrap().iter().map(|entry| entry.head.getId().to_string()).collect();
+    assert_eq!(ids, vec!["plain", "gzip"]);
+
+    let source = fs::read(testPath.join("source.txt")).unwrap();
+    assert_eq!(carrier.read_to_vec("plain").unwrap(), source);
+    assert_eq!(carrier.read_to_vec("gzip").unwrap(), source);
+    assert!(carrier.read_to_vec("missing").is_err());
+
+    let outputPath = carrier.export("gzip", &testPath.join("output.txt")).unwrap();
+    assert_eq!(fs::read(outputPath).unwrap(), source);
+}
+
+use crate::index::ResourceIndex;
+
+/// 资源索引测试(包括没有索引的旧载体文件)
+#[test]
+fn indexTest() {
+    let testPath = tempTestPath("index");
+    let targetPath = testPath.join("target.exe");
+
+    // 模拟旧版本写入的载体文件(没有资源索引)
+    let mut targetFile = fs::OpenOptions::new().write(true).open(&targetPath).unwrap();
+    std::io::Seek::seek(&mut targetFile, std::io::SeekFrom::End(0)).unwrap();
+    crate::core::writeResource(&mut targetFile, &testPath.join("source.txt"), "legacy", &Default::default()).unwrap();
+    drop(targetFile);
+    assert!(ResourceIndex::read(&mut fs::File::open(&targetPath).unwrap()).unwrap().is_none());
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add(&testPath.join("source.txt"), "indexed", Some(1)).unwrap();
+
+    let (_, index) = ResourceIndex::read(&mut fs::File::open(&targetPath).unwrap()).unwrap().unwrap();
+    let ids: Vec<&str> = index.Entries.iter().map(|entry| entry.Id.as_str()).collect();
+    assert_eq!(ids, vec!["legacy", "indexed"]);
+
+    let source = fs::read(testPath.join("source.txt")).unwrap();
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert_eq!(carrier.read_to_vec("legacy").unwrap(), source);
+    assert_eq!(carrier.read_to_vec("indexed").unwrap(), source);
+}
+
+use crate::list::{listResources, formatList, ListFormat};
+
+/// 列出资源测试
+#[test]
+fn listTest() {
+    let testPath = tempTestPath("list");
+    let mut carrier = Carrier::open(testPath.join("target.exe")).unwrap();
+    carrier.add(&testPath.join("source.txt"), "a,b", None).unwrap();
+    carrier.add(&testPath.join("source.txt"), "gzip", Some(5)).unwrap();
+
+    let infos = listResources(&testPath.join("target.exe")).unwrap();
+    assert_eq!(infos.len(), 2);
+    assert_eq!(infos[0].Offset, "MZ host executable".len() as u64);
+    assert_eq!(infos[1].Size, 9000);
+
+    let csv = formatList(&infos, ListFormat::Csv);
+    assert!(csv.starts_with("ID,Name,Length,Size,Compress,Version,Offset\n\"a,b\",source.txt,9000,9000,none,2.0.0,18\n"));
+
+    let json: serde_json::Value = serde_json::from_str(&formatList(&infos, ListFormat::Json)).unwrap();
+    assert_eq!(json[1]["Id"], "gzip");
+    assert_eq!(json[1]["Compress"], "gzip");
+
+    let table = formatList(&infos, ListFormat::Table);
+    assert_eq!(table.lines().count(), 3);
+}
+
+/// 删除资源测试
+#[test]
+fn removeTest() {
+    let testPath = tempTestPath("remove");
+    let targetPath = testPath.join("target.exe");
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add(&testPath.join("source.txt"), "1", None).unwrap();
+    carrier.add(&testPath.join("source.txt"), "2", Some(3)).unwrap();
+    carrier.add(&testPath.join("source.txt"), "3", None).unwrap();
+
+    carrier.remove("2").unwrap();
+    assert!(carrier.remove("2").is_err());
+
+    let source = fs::read(testPath.join("source.txt")).unwrap();
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    let ids: Vec<&str> = carrier.entries().unwrap().iter().map(|entry| entry.head.getId()).collect();
+    assert_eq!(ids, vec!["1", "3"]);
+    assert_eq!(carrier.read_to_vec("3").unwrap(), source);
+
+    // 删除所有资源后还原为原文件
+    carrier.remove("1").unwrap();
+    carrier.remove("3").unwrap();
+    assert_eq!(fs::read(&targetPath).unwrap(), b"MZ host executable");
+}
+
+/// 重复资源ID及替换资源测试
+#[test]
+fn updateTest() {
+    let testPath = tempTestPath("update");
+    let targetPath = testPath.join("target.exe");
+    fs::write(testPath.join("new.txt"), "new content").unwrap();
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add(&testPath.join("source.txt"), "1", None).unwrap();
+    carrier.add(&testPath.join("source.txt"), "2", None).unwrap();
+    assert!(carrier.add(&testPath.join("new.txt"), "1", None).is_err());
+    assert!(carrier.update(&testPath.join("new.txt"), "3", None).is_err());
+
+    carrier.update(&testPath.join("new.txt"), "1", Some(6)).unwrap();
+    carrier.update(&testPath.join("new.txt"), "2", None).unwrap();
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert_eq!(carrier.entries().unwrap().len(), 2);
+    assert_eq!(carrier.read_to_vec("1").unwrap(), b"new content");
+    assert_eq!(carrier.read_to_vec("2").unwrap(), b"new content");
+}
+
+/// 压缩资源不产生临时文件测试
+#[test]
+fn streamCompressionTest() {
+    let testPath = tempTestPath("stream");
+    let targetPath = testPath.join("target.exe");
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add(&testPath.join("source.txt"), "1", Some(9)).unwrap();
+
+    let entry = carrier.find("1").unwrap().unwrap();
+    assert_eq!(entry.head.getSize(), 9000);
+    assert!(entry.head.getLength() < 9000);
+
+    carrier.export("1", &testPath).unwrap();
+    let mut files: Vec<String> = fs::read_dir(&testPath).unwrap().map(|file| file.unwrap().file_name().into_string().unwrap()).collect();
+    files.sort();
+    assert_eq!(files, vec!["source.txt", "target.exe"]);
+}
+
+use crate::core::AddOptions;
+
+/// 各压缩模式测试
+#[test]
+fn codecTest() {
+    let testPath = tempTestPath("codec");
+    let source = fs::read(testPath.join("source.txt")).unwrap();
+    let mut carrier = Carrier::open(testPath.join("target.exe")).unwrap();
+
+    for name in CompressMode::NAMES.iter() {
+        let compressMode = CompressMode::from_name(name).unwrap();
+        let options = AddOptions { compressMode, compressionGrade: 3, ..Default::default() };
+        match compressMode.codec() {
+            Ok(_) => {
+                carrier.add_with(&testPath.join("source.txt"), name, &options).unwrap();
+                assert_eq!(carrier.find(name).unwrap().unwrap().head.getCompressMode(), compressMode);
+                assert_eq!(carrier.read_to_vec(name).unwrap(), source);
+            }
+            // 未启用对应功能时拒绝写入
+            Err(_) => assert!(carrier.add_with(&testPath.join("source.txt"), name, &options).is_err()),
+        }
+    }
+
+    let options = AddOptions { compressMode: CompressMode::Compress, compressionGrade: 10, ..Default::default() };
+    assert!(carrier.add_with(&testPath.join("source.txt"), "grade", &options).is_err());
+}
+
+/// 资源摘要测试(包括没有摘要的 1.0.0 版本资源)
+#[test]
+fn digestTest() {
+    let testPath = tempTestPath("digest");
+    let targetPath = testPath.join("target.exe");
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add(&testPath.join("source.txt"), "1", None).unwrap();
+    let entry = carrier.find("1").unwrap().unwrap();
+    assert_eq!(entry.head.getDigest().len(), 32);
+
+    // 修改资源数据中的一个字节(长度不变)
+    let mut data = fs::read(&targetPath).unwrap();
+    data[entry.dataOffset() as usize + 100] ^= 0xFF;
+    fs::write(&targetPath, &data).unwrap();
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert!(carrier.export("1", &testPath.join("output.txt")).is_err());
+    assert!(!testPath.join("output.txt").exists());
+
+    // 1.0.0 版本资源
+    let legacy = Astruct1_0 {
+        Head: HEAD_MAGIC_V1.to_vec(),
+        Version: "1.0.0".to_string(),
+        Id: format!("{:^64}", "legacy"),
+        Name: format!("{:^255}", "legacy.txt"),
+        Length: format!("{:0>13}", 6),
+        Size: format!("{:0>13}", 6),
+        Compress: CompressMode::None,
+    };
+    let legacyPath = testPath.join("legacy.exe");
+    let mut data = b"MZ host executable".to_vec();
+    data.extend(bincode::serialize(&legacy).unwrap());
+    data.extend(b"legacyODEND");
+    fs::write(&legacyPath, &data).unwrap();
+
+    let mut carrier = Carrier::open(&legacyPath).unwrap();
+    assert_eq!(carrier.entries().unwrap()[0].head.getVersion(), "1.0.0");
+    assert!(carrier.entries().unwrap()[0].head.getDigest().is_empty());
+    assert_eq!(carrier.read_to_vec("legacy").unwrap(), b"legacy");
+}
+
+/// 1.0.0 版本资源文件头
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Astruct1_0 {
+    Head: Vec<u8>,
+    Version: String,
+    Id: String,
+    Name: String,
+    Length: String,
+    Size: String,
+    Compress: CompressMode,
+}
+
+/// 校验资源测试
+#[test]
+fn verifyTest() {
+    let testPath = tempTestPath("verify");
+    let targetPath = testPath.join("target.exe");
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add(&testPath.join("source.txt"), "1", Some(1)).unwrap();
+    carrier.add(&testPath.join("source.txt"), "2", None).unwrap();
+    assert_eq!(carrier.verify("1").unwrap(), 9000);
+    assert_eq!(carrier.verify("2").unwrap(), 9000);
+
+    // 破坏第二个资源的尾部标识
+    let entry = carrier.find("2").unwrap().unwrap();
+    let mut data = fs::read(&targetPath).unwrap();
+    data[entry.endOffset() as usize] = 0;
+    fs::write(&targetPath, &data).unwrap();
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert!(carrier.verify("1").is_ok());
+    assert!(carrier.verify("2").is_err());
+    assert!(carrier.verify("3").is_err());
+}
+
+use crate::crypto::{Cipher, Secret};
+
+/// 加密资源测试
+#[cfg(feature = "encryption")]
+#[test]
+fn encryptionTest() {
+    let testPath = tempTestPath("encryption");
+    let targetPath = testPath.join("target.exe");
+    // 超过一个加密分块
+    let sourcePath = testPath.join("large.bin");
+    let source: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    fs::write(&sourcePath, &source).unwrap();
+    let keyPath = testPath.join("secret.key");
+    fs::write(&keyPath, [7u8; 32]).unwrap();
+    let key = Secret::from_key_file(&keyPath).unwrap();
+    assert!(Secret::from_key_file(&sourcePath).is_err());
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    let password = AddOptions { compressMode: CompressMode::Compress, compressionGrade: 6, secret: Some(Secret::Password("123456".to_string())), ..Default::default() };
+    carrier.add_with(&sourcePath, "password", &password).unwrap();
+    let keyFile = AddOptions { secret: Some(key.clone()), ..Default::default() };
+    carrier.add_with(&sourcePath, "key", &keyFile).unwrap();
+    let entry = carrier.find("password").unwrap().unwrap();
+    assert_eq!(entry.head.getCipher(), Cipher::Aes256Gcm);
+
+    // 未提供密钥或密钥错误
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert!(carrier.read_to_vec("password").is_err());
+    carrier.set_secret(Some(Secret::Password("654321".to_string())));
+    assert!(carrier.read_to_vec("password").is_err());
+    assert!(carrier.read_to_vec("key").is_err());
+
+    carrier.set_secret(Some(Secret::Password("123456".to_string())));
+    assert_eq!(carrier.read_to_vec("password").unwrap(), source);
+    // 派生一次的密钥可以直接解密
+    let derived = Secret::Password("123456".to_string()).derive(entry.head.getSalt()).unwrap();
+    assert!(!derived.needSalt());
+    carrier.set_secret(Some(derived));
+    assert_eq!(carrier.read_to_vec("password").unwrap(), source);
+    carrier.set_secret(Some(key.clone()));
+    assert_eq!(carrier.read_to_vec("key").unwrap(), source);
+
+    // 篡改密文
+    let entry = carrier.find("key").unwrap().unwrap();
+    let mut data = fs::read(&targetPath).unwrap();
+    data[entry.dataOffset() as usize + 100] ^= 1;
+    fs::write(&targetPath, &data).unwrap();
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.set_secret(Some(key));
+    assert!(carrier.read_to_vec("key").is_err());
+}
+
+/// 释放失败时保留已存在的输出文件测试
+#[test]
+fn exportKeepOutputTest() {
+    let testPath = tempTestPath("export_keep_output");
+    let targetPath = testPath.join("target.exe");
+    let sourcePath = testPath.join("source.bin");
+    let source: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    fs::write(&sourcePath, &source).unwrap();
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add(&sourcePath, "corrupted", None).unwrap();
+    if cfg!(feature = "encryption") {
+        let password = AddOptions { secret: Some(Secret::Password("123456".to_string())), ..Default::default() };
+        carrier.add_with(&sourcePath, "encrypted", &password).unwrap();
+    }
+    // 修改资源数据(写出后摘要校验失败)
+    let entry = carrier.find("corrupted").unwrap().unwrap();
+    let mut data = fs::read(&targetPath).unwrap();
+    data[entry.dataOffset() as usize + 100] ^= 1;
+    fs::write(&targetPath, &data).unwrap();
+
+    let outputPath = testPath.join("output.bin");
+    fs::write(&outputPath, b"existing").unwrap();
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert!(carrier.export("corrupted", &outputPath).is_err());
+    assert_eq!(fs::read(&outputPath).unwrap(), b"existing");
+    // 不残留临时文件
+    assert_eq!(fs::read_dir(&testPath).unwrap().count(), 4);
+
+    // 未提供密钥、密钥错误
+    if cfg!(feature = "encryption") {
+        assert!(carrier.export("encrypted", &outputPath).is_err());
+        carrier.set_secret(Some(Secret::Password("654321".to_string())));
+        assert!(carrier.export("encrypted", &outputPath).is_err());
+        assert_eq!(fs::read(&outputPath).unwrap(), b"existing");
+        assert_eq!(fs::read_dir(&testPath).unwrap().count(), 4);
+
+        carrier.set_secret(Some(Secret::Password("123456".to_string())));
+        carrier.export("encrypted", &outputPath).unwrap();
+        assert_eq!(fs::read(&outputPath).unwrap(), source);
+        assert_eq!(fs::read_dir(&testPath).unwrap().count(), 4);
+    }
+}
+
+use crate::pe::PeImage;
+use crate::core::findResources;
+
+/// 生成最小的 PE32+ 文件(文件头 0x200 字节 + 一个 .text 节)
+fn buildPe(text: &[u8]) -> Vec<u8> {
+    let rawSize = text.len().div_ceil(0x200) * 0x200;
+    let mut data = vec![0u8; 0x200 + rawSize];
+    data[0..2].copy_from_slice(b"MZ");
+    data[0x3C..0x40].copy_from_slice(&0x40u32.to_le_bytes());
+    data[0x40..0x44].copy_from_slice(b"PE\0\0");
+    // COFF 文件头
+    data[0x44..0x46].copy_from_slice(&0x8664u16.to_le_bytes());
+    data[0x46..0x48].copy_from_slice(&1u16.to_le_bytes());
+    data[0x54..0x56].copy_from_slice(&0xF0u16.to_le_bytes());
+    data[0x56..0x58].copy_from_slice(&0x22u16.to_le_bytes());
+    // 可选头
+    let optional = 0x58;
+    data[optional..optional + 2].copy_from_slice(&0x20Bu16.to_le_bytes());
+    data[optional + 32..optional + 36].copy_from_slice(&0x1000u32.to_le_bytes());
+    data[optional + 36..optional + 40].copy_from_slice(&0x200u32.to_le_bytes());
+    data[optional + 56..optional + 60].copy_from_slice(&(0x1000 + rawSize as u32).to_le_bytes());
+    data[optional + 60..optional + 64].copy_from_slice(&0x200u32.to_le_bytes());
+    data[optional + 108..optional + 112].copy_from_slice(&16u32.to_le_bytes());
+    // 节表
+    let section = optional + 0xF0;
+    data[section..section + 5].copy_from_slice(b".text");
+    data[section + 8..section + 12].copy_from_slice(&(text.len() as u32).to_le_bytes());
+    data[section + 12..section + 16].copy_from_slice(&0x1000u32.to_le_bytes());
+    data[section + 16..section + 20].copy_from_slice(&(rawSize as u32).to_le_bytes());
+    data[section + 20..section + 24].copy_from_slice(&0x200u32.to_le_bytes());
+    data[0x200..0x200 + text.len()].copy_from_slice(text);
+    data
+}
+
+/// PE 附加数据测试
+#[test]
+fn peOverlayTest() {
+    let testPath = tempTestPath("pe");
+    let targetPath = testPath.join("target.exe");
+    // .text 节中包含完整的资源文件头
+    let fakeHead = ResourceHead::new("fake", 0, 0, "fake.txt", CompressMode::None).to_bytes().unwrap();
+    let mut data = buildPe(&fakeHead);
+    let imageEnd = data.len() as u64;
+    data.extend_from_slice(b"foreign overlay");
+    fs::write(&targetPath, &data).unwrap();
+
+    let mut file = fs::File::open(&targetPath).unwrap();
+    let image = PeImage::parse(&mut file).unwrap().unwrap();
+    assert!(image.is64);
+    assert_eq!(image.sections[0].name, ".text");
+    assert_eq!(image.imageEnd, imageEnd);
+    assert_eq!(crate::format::ImageInfo::detect(&mut file).imageEnd, imageEnd);
+    assert_eq!(PeImage::parse(&mut fs::File::open(testPath.join("source.txt")).unwrap()).unwrap(), None);
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert!(carrier.entries().unwrap().is_empty());
+    assert_eq!(carrier.foreign_overlay().unwrap(), Some((imageEnd, 15)));
+    carrier.add(&testPath.join("source.txt"), "1", None).unwrap();
+    assert_eq!(carrier.foreign_overlay().unwrap(), Some((imageEnd, 15)));
+
+    // 扫描只从附加数据开始
+    let entries = findResources(&mut fs::File::open(&targetPath).unwrap()).unwrap();
+    let ids: Vec<&str> = entries.iter().map(|entry| entry.head.getId()).collect();
+    assert_eq!(ids, vec!["1"]);
+    assert_eq!(entries[0].offset, imageEnd + 15);
+}
+
+use crate::pe::SignatureMode;
+use std::convert::TryInto;
+
+/// 在 PE 文件末尾增加证书表(WIN_CERTIFICATE)
+fn signPe(data: &mut Vec<u8>, certificate: &[u8]) {
+    let address = data.len();
+    let length = 8 + certificate.len();
+    data.extend_from_slice(&(length as u32).to_le_bytes());
+    data.extend_from_slice(&0x200u16.to_le_bytes());
+    data.extend_from_slice(&2u16.to_le_bytes());
+    data.extend_from_slice(certificate);
+    data.resize(address + length.div_ceil(8) * 8, 0);
+    let directory = 0x58 + 144;
+    data[directory..directory + 4].copy_from_slice(&(address as u32).to_le_bytes());
+    let size = (data.len() - address) as u32;
+    data[directory + 4..directory + 8].copy_from_slice(&size.to_le_bytes());
+}
+
+/// 损坏的 PE 文件头返回对应的退出码测试
+#[test]
+fn corruptPeTest() {
+    use crate::error::{exitCode, EXIT_CORRUPT, EXIT_INVALID_HEADER};
+    let testPath = tempTestPath("corruptPe");
+    let targetPath = testPath.join("target.exe");
+    let sourcePath = testPath.join("source.txt");
+
+    // 节表超出文件范围
+    let mut data = buildPe(b"code");
+    data[0x46..0x48].copy_from_slice(&0xFFFFu16.to_le_bytes());
+    fs::write(&targetPath, &data).unwrap();
+    let error = PeImage::parse(&mut fs::File::open(&targetPath).unwrap()).unwrap_err();
+    assert_eq!(exitCode(&*error), EXIT_CORRUPT);
+
+    // 证书表被截断
+    let mut data = buildPe(b"code");
+    signPe(&mut data, &[0x30; 13]);
+    data.truncate(data.len() - 8);
+    fs::write(&targetPath, &data).unwrap();
+    let error = Carrier::open(&targetPath).unwrap().add(&sourcePath, "1", None).unwrap_err();
+    assert_eq!(exitCode(&*error), EXIT_CORRUPT);
+
+    // 证书长度无效
+    let mut data = buildPe(b"code");
+    let address = data.len();
+    signPe(&mut data, &[0x30; 13]);
+    data[address..address + 4].copy_from_slice(&0u32.to_le_bytes());
+    fs::write(&targetPath, &data).unwrap();
+    let error = Carrier::open(&targetPath).unwrap().add(&sourcePath, "1", None).unwrap_err();
+    assert_eq!(exitCode(&*error), EXIT_INVALID_HEADER);
+}
+
+/// 带数字签名的 PE 文件增加资源测试
+#[test]
+fn signatureTest() {
+    let testPath = tempTestPath("signature");
+    let targetPath = testPath.join("target.exe");
+    let sourcePath = testPath.join("source.txt");
+    let mut original = buildPe(b"code");
+    let address = original.len();
+    signPe(&mut original, &[0x30; 13]);
+    fs::write(&targetPath, &original).unwrap();
+
+    let certificateTable = |data: &[u8]| -> (usize, usize) {
+        let directory = 0x58 + 144;
+        let size = u32::from_le_bytes(data[directory + 4..directory + 8].try_into().unwrap()) as usize;
+        let length = u32::from_le_bytes(data[address..address + 4].try_into().unwrap()) as usize;
+        (size, length)
+    };
+
+    // 嵌入证书表: 证书表延伸到文件末尾，签名覆盖的内容不变
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add(&sourcePath, "1", Some(1)).unwrap();
+    carrier.add(&sourcePath, "2", None).unwrap();
+    let data = fs::read(&targetPath).unwrap();
+    assert_eq!(certificateTable(&data), (data.len() - address, data.len() - address));
+    assert_eq!((data.len() - address) % 8, 0);
+    // 签名摘要不包括校验和、安全数据目录及证书表
+    assert_eq!(data[..0x58 + 64], original[..0x58 + 64]);
+    assert_eq!(data[0x58 + 68..0x58 + 144], original[0x58 + 68..0x58 + 144]);
+    assert_eq!(data[0x58 + 152..address], original[0x58 + 152..address]);
+    assert_eq!(data[address + 4..original.len()], original[address + 4..]);
+    assert_eq!(carrier.foreign_overlay().unwrap(), None);
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert_eq!(carrier.read_to_vec("1").unwrap(), fs::read(&sourcePath).unwrap());
+    carrier.update(&sourcePath, "1", None).unwrap();
+    let data = fs::read(&targetPath).unwrap();
+    assert_eq!(certificateTable(&data), (data.len() - address, data.len() - address));
+    assert_eq!((data.len() - address) % 8, 0);
+
+    // 删除所有资源后恢复原证书表长度(最后一个证书的长度包含填充)
+    carrier.remove("1").unwrap();
+    carrier.remove("2").unwrap();
+    let data = fs::read(&targetPath).unwrap();
+    assert_eq!(certificateTable(&data), (original.len() - address, original.len() - address));
+    assert_eq!(data[..0x58 + 64], original[..0x58 + 64]);
+    assert_eq!(data[0x58 + 68..address], original[0x58 + 68..address]);
+    assert_eq!(data[address + 4..], original[address + 4..]);
+    fs::write(&targetPath, &original).unwrap();
+
+    // 拒绝修改、直接追加
+    let refuse = AddOptions { signature: SignatureMode::Refuse, ..Default::default() };
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert!(carrier.add_with(&sourcePath, "1", &refuse).is_err());
+    assert_eq!(fs::read(&targetPath).unwrap(), original);
+    let append = AddOptions { signature: SignatureMode::Append, ..Default::default() };
+    carrier.add_with(&sourcePath, "1", &append).unwrap();
+    let data = fs::read(&targetPath).unwrap();
+    assert_eq!(certificateTable(&data), (original.len() - address, 8 + 13));
+    assert_eq!(Carrier::open(&targetPath).unwrap().read_to_vec("1").unwrap(), fs::read(&sourcePath).unwrap());
+}
+
+use crate::pe::computeChecksum;
+
+/// PE 校验和测试
+#[test]
+fn checksumTest() {
+    let testPath = tempTestPath("checksum");
+    let targetPath = testPath.join("target.exe");
+    // 奇数长度的 .text 节及附加数据
+    let mut data = buildPe(&[0xFF; 0x1FF]);
+    data.push(0xAB);
+    fs::write(&targetPath, &data).unwrap();
+
+    // 按定义逐个 16 位字计算
+    let expected = |data: &[u8]| -> u32 {
+        let mut sum = 0u64;
+        for (i, word) in data.chunks(2).enumerate() {
+            if i * 2 == 0x58 + 64 || i * 2 == 0x58 + 66 {
+                continue;
+            }
+            sum += u16::from_le_bytes([word[0], *word.get(1).unwrap_or(&0)]) as u64;
+            sum = (sum & 0xFFFF) + (sum >> 16);
+        }
+        (sum as u32) + data.len() as u32
+    };
+    let mut file = fs::File::open(&targetPath).unwrap();
+    assert_eq!(computeChecksum(&mut file, 0x58 + 64).unwrap(), expected(&data));
+
+    // 增加、删除资源后重新计算校验和
+    let storedChecksum = |path: &std::path::Path| -> u32 {
+        let data = fs::read(path).unwrap();
+        u32::from_le_bytes(data[0x58 + 64..0x58 + 68].try_into().unwrap())
+    };
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add(&testPath.join("source.txt"), "1", Some(1)).unwrap();
+    assert_eq!(storedChecksum(&targetPath), expected(&fs::read(&targetPath).unwrap()));
+    carrier.remove("1").unwrap();
+    assert_eq!(storedChecksum(&targetPath), expected(&data));
+
+    // 保留校验和
+    let options = AddOptions { keepChecksum: true, ..Default::default() };
+    carrier.add_with(&testPath.join("source.txt"), "1", &options).unwrap();
+    assert_eq!(storedChecksum(&targetPath), expected(&data));
+}
+
+use crate::format::{ImageInfo, CarrierFormat};
+
+/// 生成最小的 64位 ELF 文件(一个可加载段，.text 节中为指定数据，节头表位于末尾)
+fn buildElf(text: &[u8]) -> Vec<u8> {
+    let sectionTable = (0x100 + text.len()).div_ceil(8) * 8;
+    let mut data = vec![0u8; sectionTable + 3 * 64];
+    data[0..4].copy_from_slice(b"\x7FELF");
+    data[4] = 2;
+    data[5] = 1;
+    data[6] = 1;
+    data[0x20..0x28].copy_from_slice(&0x40u64.to_le_bytes());
+    data[0x28..0x30].copy_from_slice(&(sectionTable as u64).to_le_bytes());
+    data[0x34..0x36].copy_from_slice(&0x40u16.to_le_bytes());
+    data[0x36..0x38].copy_from_slice(&56u16.to_le_bytes());
+    data[0x38..0x3A].copy_from_slice(&1u16.to_le_bytes());
+    data[0x3A..0x3C].copy_from_slice(&64u16.to_le_bytes());
+    data[0x3C..0x3E].copy_from_slice(&3u16.to_le_bytes());
+    // PT_LOAD
+    data[0x40..0x44].copy_from_slice(&1u32.to_le_bytes());
+    data[0x60..0x68].copy_from_slice(&((0x100 + text.len()) as u64).to_le_bytes());
+    data[0x100..0x100 + text.len()].copy_from_slice(text);
+    // .text 及 .bss(不占用文件空间)
+    let text = sectionTable + 64;
+    data[text + 4..text + 8].copy_from_slice(&1u32.to_le_bytes());
+    data[text + 24..text + 32].copy_from_slice(&0x100u64.to_le_bytes());
+    data[text + 32..text + 40].copy_from_slice(&((sectionTable - 0x100) as u64).to_le_bytes());
+    let bss = sectionTable + 128;
+    data[bss + 4..bss + 8].copy_from_slice(&8u32.to_le_bytes());
+    let end = data.len() as u64;
+    data[bss + 24..bss + 32].copy_from_slice(&end.to_le_bytes());
+    data[bss + 32..bss + 40].copy_from_slice(&0x10000u64.to_le_bytes());
+    data
+}
+
+/// 生成最小的 64位 Mach-O 文件(__LINKEDIT 段末尾为代码签名)
+fn buildMachO() -> Vec<u8> {
+    let mut data = vec![0u8; 0x200];
+    data[0..4].copy_from_slice(&0xFEEDFACFu32.to_le_bytes());
+    data[4..8].copy_from_slice(&0x0100_0007u32.to_le_bytes());
+    data[16..20].copy_from_slice(&2u32.to_le_bytes());
+    data[20..24].copy_from_slice(&88u32.to_le_bytes());
+    // LC_SEGMENT_64 __LINKEDIT
+    data[32..36].copy_from_slice(&0x19u32.to_le_bytes());
+    data[36..40].copy_from_slice(&72u32.to_le_bytes());
+    data[40..50].copy_from_slice(b"__LINKEDIT");
+    data[72..80].copy_from_slice(&0x100u64.to_le_bytes());
+    data[80..88].copy_from_slice(&0x100u64.to_le_bytes());
+    // LC_CODE_SIGNATURE
+    data[104..108].copy_from_slice(&0x1Du32.to_le_bytes());
+    data[108..112].copy_from_slice(&16u32.to_le_bytes());
+    data[112..116].copy_from_slice(&0x180u32.to_le_bytes());
+    data[116..120].copy_from_slice(&0x80u32.to_le_bytes());
+    data
+}
+
+/// ELF、Mach-O 载体格式测试
+#[test]
+fn formatTest() {
+    let testPath = tempTestPath("format");
+    let sourcePath = testPath.join("source.txt");
+    let detect = |path: &std::path::Path| ImageInfo::detect(&mut fs::File::open(path).unwrap());
+
+    // ELF: 节头表位于末尾，.bss 不占用文件空间，节数据中的资源文件头不会被识别
+    let elfPath = testPath.join("target.elf");
+    let fakeHead = ResourceHead::new("fake", 0, 0, "fake.txt", CompressMode::None).to_bytes().unwrap();
+    let elf = buildElf(&fakeHead);
+    fs::write(&elfPath, &elf).unwrap();
+    assert_eq!(detect(&elfPath), ImageInfo { format: CarrierFormat::Elf, imageEnd: elf.len() as u64, signed: false });
+    let mut carrier = Carrier::open(&elfPath).unwrap();
+    assert!(carrier.entries().unwrap().is_empty());
+    carrier.add(&sourcePath, "1", None).unwrap();
+    assert_eq!(carrier.foreign_overlay().unwrap(), None);
+    let entries = findResources(&mut fs::File::open(&elfPath).unwrap()).unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].offset, elf.len() as u64);
+
+    // Mach-O: 代码签名位于 __LINKEDIT 末尾
+    let machoPath = testPath.join("target.macho");
+    let macho = buildMachO();
+    fs::write(&machoPath, &macho).unwrap();
+    assert_eq!(detect(&machoPath), ImageInfo { format: CarrierFormat::MachO, imageEnd: 0x200, signed: true });
+    let refuse = AddOptions { signature: SignatureMode::Refuse, ..Default::default() };
+    assert!(Carrier::open(&machoPath).unwrap().add_with(&sourcePath, "1", &refuse).is_err());
+    let mut carrier = Carrier::open(&machoPath).unwrap();
+    carrier.add(&sourcePath, "1", None).unwrap();
+    assert_eq!(carrier.entries().unwrap()[0].offset, 0x200);
+
+    // 通用二进制(Fat)
+    let fatPath = testPath.join("target.fat");
+    let mut fat = vec![0u8; 0x1000];
+    fat[0..4].copy_from_slice(&0xCAFEBABEu32.to_be_bytes());
+    fat[4..8].copy_from_slice(&1u32.to_be_bytes());
+    fat[8..12].copy_from_slice(&0x0100_0007u32.to_be_bytes());
+    fat[16..20].copy_from_slice(&0x1000u32.to_be_bytes());
+    fat[20..24].copy_from_slice(&0x200u32.to_be_bytes());
+    fat[24..28].copy_from_slice(&12u32.to_be_bytes());
+    fat.extend_from_slice(&macho);
+    fat.extend_from_slice(b"foreign");
+    fs::write(&fatPath, &fat).unwrap();
+    assert_eq!(detect(&fatPath), ImageInfo { format: CarrierFormat::FatMachO, imageEnd: 0x1200, signed: true });
+    assert_eq!(Carrier::open(&fatPath).unwrap().foreign_overlay().unwrap(), Some((0x1200, 7)));
+
+    // 其他文件
+    assert_eq!(detect(&sourcePath), ImageInfo::default());
+}
+
+use std::io::{Read, Seek, SeekFrom};
+
+/// 资源读取器测试(随机访问解压后的资源数据)
+#[test]
+fn readerTest() {
+    let testPath = tempTestPath("reader");
+    let targetPath = testPath.join("target.exe");
+    let sourcePath = testPath.join("large.bin");
+    let source: Vec<u8> = (0..300_000u32).map(|i| (i % 251) as u8).collect();
+    fs::write(&sourcePath, &source).unwrap();
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add(&sourcePath, "none", None).unwrap();
+    carrier.add(&sourcePath, "gzip", Some(6)).unwrap();
+    let mut ids = vec!["none", "gzip"];
+    if cfg!(feature = "encryption") {
+        let secret = AddOptions { secret: Some(Secret::Password("123456".to_string())), ..Default::default() };
+        carrier.add_with(&sourcePath, "encrypted", &secret).unwrap();
+        carrier.set_secret(Some(Secret::Password("123456".to_string())));
+        ids.push("encrypted");
+    }
+
+    for id in ids {
+        let mut reader = carrier.open_resource(id).unwrap();
+        assert_eq!(reader.size(), source.len() as u64);
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data).unwrap();
+        assert_eq!(data, source);
+
+        // 向后、向前及从末尾定位
+        let mut buffer = [0u8; 100];
+        reader.seek(SeekFrom::Start(150_000)).unwrap();
+        reader.read_exact(&mut buffer).unwrap();
+        assert_eq!(buffer[..], source[150_000..150_100]);
+        reader.seek(SeekFrom::Current(-50_100)).unwrap();
+        reader.read_exact(&mut buffer).unwrap();
+        assert_eq!(buffer[..], source[100_000..100_100]);
+        assert_eq!(reader.seek(SeekFrom::End(-10)).unwrap(), source.len() as u64 - 10);
+        assert_eq!(reader.read(&mut buffer).unwrap(), 10);
+        assert_eq!(buffer[..10], source[source.len() - 10..]);
+        assert_eq!(reader.read(&mut buffer).unwrap(), 0);
+        assert!(reader.seek(SeekFrom::Current(-400_000)).is_err());
+    }
+
+    // 从头读取时校验摘要
+    let entry = carrier.find("none").unwrap().unwrap();
+    let mut data = fs::read(&targetPath).unwrap();
+    data[entry.dataOffset() as usize + 10] ^= 1;
+    fs::write(&targetPath, &data).unwrap();
+    let mut reader = Carrier::open(&targetPath).unwrap().open_resource("none").unwrap();
+    assert!(reader.read_to_end(&mut Vec::new()).is_err());
+    assert!(Carrier::open(&targetPath).unwrap().open_resource("missing").is_err());
+
+    // 当前程序没有附加资源(只扫描附加数据，不会识别程序中的资源标识)
+    assert!(Carrier::current_exe().unwrap().entries().unwrap().is_empty());
+    assert!(Carrier::current_exe().unwrap().open_resource("Archive").is_err());
+}
+
+/// 资源数据对齐及内存映射读取测试
+#[test]
+fn alignmentTest() {
+    let testPath = tempTestPath("alignment");
+    let targetPath = testPath.join("target.exe");
+    let sourcePath = testPath.join("table.bin");
+    let source: Vec<u8> = (0..10_000u32).map(|i| (i % 253) as u8).collect();
+    fs::write(&sourcePath, &source).unwrap();
+    let aligned = AddOptions { alignment: 4096, ..Default::default() };
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add(&testPath.join("source.txt"), "small", None).unwrap();
+    carrier.add_with(&sourcePath, "font", &aligned).unwrap();
+    carrier.add_with(&sourcePath, "table", &aligned).unwrap();
+    carrier.add(&sourcePath, "gzip", Some(6)).unwrap();
+    let invalid = AddOptions { alignment: 3, ..Default::default() };
+    assert!(carrier.add_with(&sourcePath, "invalid", &invalid).is_err());
+    for id in ["font", "table"] {
+        assert_eq!(carrier.find(id).unwrap().unwrap().dataOffset() % 4096, 0);
+    }
+
+    // 删除、替换资源后其后的资源仍然对齐，扫描时可以找到填充之后的资源
+    carrier.remove("small").unwrap();
+    carrier.update_with(&sourcePath, "font", &aligned).unwrap();
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    let ids: Vec<String> = carrier.entries().unwrap().iter().map(|entry| entry.head.getId().to_string()).collect();
+    assert_eq!(ids, ["table", "gzip", "font"]);
+    for id in ["font", "table"] {
+        assert_eq!(carrier.find(id).unwrap().unwrap().dataOffset() % 4096, 0);
+        assert_eq!(carrier.read_to_vec(id).unwrap(), source);
+    }
+    let mut file = fs::File::open(&targetPath).unwrap();
+    assert_eq!(findResources(&mut file).unwrap().len(), 3);
+
+    // 内存映射读取未压缩的资源
+    #[cfg(feature = "mmap")]
+    {
+        let mapped = carrier.map().unwrap();
+        assert_eq!(mapped.get("table").unwrap(), &source[..]);
+        assert_eq!(mapped.get("font").unwrap().as_ptr() as usize % 4096, 0);
+        mapped.verify("font").unwrap();
+        assert!(mapped.get("gzip").is_err());
+        assert!(mapped.get("missing").is_err());
+    }
+}
+
+/// 从输入流增加资源、释放资源到输出流测试
+#[test]
+fn streamTest() {
+    let testPath = tempTestPath("stream");
+    let targetPath = testPath.join("target.exe");
+    let source = "Appender stream ".repeat(1000).into_bytes();
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add_from(&mut &source[..], "Data", &AddOptions::compression(Some(6))).unwrap();
+    let named = AddOptions { name: Some("data.tar".to_string()), ..Default::default() };
+    carrier.add_with(&testPath.join("source.txt"), "File", &named).unwrap();
+    crate::core::addResourceFrom(&targetPath, &mut &source[..10], "Named", &named, None).unwrap();
+
+    // 未指定文件名时使用资源ID
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert_eq!(carrier.find("Data").unwrap().unwrap().head.getName(), "Data");
+    assert_eq!(carrier.find("Named").unwrap().unwrap().head.getName(), "data.tar");
+    assert_eq!(carrier.find("File").unwrap().unwrap().head.getName(), "data.tar");
+
+    let mut output = Vec::new();
+    assert_eq!(crate::core::exportResourceTo(&targetPath, "Data", &mut output, None).unwrap(), source.len() as u64);
+    assert_eq!(output, source);
+    assert!(crate::core::exportResourceTo(&targetPath, "missing", &mut Vec::new(), None).is_err());
+
+    // 替换资源
+    let replace = AddOptions { replace: true, ..Default::default() };
+    carrier.add_from(&mut &source[..5], "Data", &replace).unwrap();
+    assert_eq!(carrier.read_to_vec("Data").unwrap(), &source[..5]);
+}
+
+/// 递归增加、释放目录测试
+#[test]
+fn directoryTest() {
+    let testPath = tempTestPath("directory");
+    let targetPath = testPath.join("target.exe");
+    let assets = testPath.join("assets");
+    fs::create_dir_all(assets.join("img").join("icons")).unwrap();
+    fs::write(assets.join("a.txt"), "a").unwrap();
+    fs::write(assets.join("img").join("b.txt"), "b".repeat(1000)).unwrap();
+    fs::write(assets.join("img").join("icons").join("c.ico"), "c").unwrap();
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add(&testPath.join("source.txt"), "source", None).unwrap();
+    let ids = carrier.add_directory(&assets, "assets/", &AddOptions::compression(Some(6))).unwrap();
+    assert_eq!(ids, ["assets/a.txt", "assets/img/b.txt", "assets/img/icons/c.ico"]);
+    assert_eq!(carrier.find("assets/img/b.txt").unwrap().unwrap().head.getName(), "img/b.txt");
+
+    // 已存在的资源ID、过长的资源ID在写入前报错，不修改载体文件
+    let before = fs::read(&targetPath).unwrap();
+    assert!(carrier.add_directory(&assets, "assets/", &AddOptions::default()).is_err());
+    assert!(carrier.add_directory(&assets, &"x".repeat(250), &AddOptions::default()).is_err());
+    assert_eq!(fs::read(&targetPath).unwrap(), before);
+    let replace = AddOptions { replace: true, ..Default::default() };
+    carrier.add_directory(&assets, "assets/", &replace).unwrap();
+    assert_eq!(Carrier::open(&targetPath).unwrap().entries().unwrap().len(), 4);
+
+    // 替换失败时原有资源保持不变
+    let before = fs::read(&targetPath).unwrap();
+    let invalid = AddOptions { replace: true, ..AddOptions::compression(Some(15)) };
+    assert!(carrier.add_directory(&assets, "assets/", &invalid).is_err());
+    assert_eq!(fs::read(&targetPath).unwrap(), before);
+    let mut reopened = Carrier::open(&targetPath).unwrap();
+    assert_eq!(reopened.entries().unwrap().len(), 4);
+    assert_eq!(reopened.read_to_vec("assets/a.txt").unwrap(), b"a");
+
+    let output = testPath.join("output");
+    let paths = carrier.export_directory("assets/", &output).unwrap();
+    assert_eq!(paths.len(), 3);
+    assert_eq!(fs::read_to_string(output.join("img").join("icons").join("c.ico")).unwrap(), "c");
+    assert_eq!(fs::read_to_string(output.join("img").join("b.txt")).unwrap(), "b".repeat(1000));
+
+    // 资源文件名不是相对路径时拒绝释放
+    let evil = AddOptions { name: Some("../evil.txt".to_string()), ..Default::default() };
+    carrier.add_from(&mut &b"evil"[..], "assets/evil", &evil).unwrap();
+    assert!(carrier.export_directory("assets/", &output).is_err());
+    assert!(!testPath.join("evil.txt").exists());
+}
+
+/// 增加目录时跳过指向目录的符号链接测试(循环链接不会无限递归)
+#[cfg(unix)]
+#[test]
+fn symlinkDirectoryTest() {
+    let testPath = tempTestPath("symlinkDirectory");
+    let assets = testPath.join("assets");
+    fs::create_dir_all(assets.join("sub")).unwrap();
+    fs::write(assets.join("sub").join("a.txt"), "a").unwrap();
+    std::os::unix::fs::symlink(&assets, assets.join("sub").join("loop")).unwrap();
+    std::os::unix::fs::symlink(testPath.join("source.txt"), assets.join("link.txt")).unwrap();
+
+    let files: Vec<String> = crate::util::listFiles(&assets).unwrap().into_iter().map(|(_, relative)| relative).collect();
+    assert_eq!(files, ["link.txt", "sub/a.txt"]);
+}
+
+use crate::carrier::ConflictPolicy;
+
+/// 释放所有资源及文件名冲突处理测试
+#[test]
+fn exportAllTest() {
+    let testPath = tempTestPath("exportAll");
+    let targetPath = testPath.join("target.exe");
+    let named = |name: &str| AddOptions { name: Some(name.to_string()), ..Default::default() };
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add_from(&mut &b"first"[..], "first", &named("same.txt")).unwrap();
+    carrier.add_from(&mut &b"second"[..], "second", &named("same.txt")).unwrap();
+    carrier.add_from(&mut &b"nested"[..], "nested", &named("dir/file")).unwrap();
+
+    // 报错时不输出任何文件
+    let output = testPath.join("output");
+    assert!(carrier.export_all(&output, ConflictPolicy::Error).is_err());
+    assert!(!output.exists());
+
+    assert_eq!(carrier.export_all(&output, ConflictPolicy::Overwrite).unwrap().len(), 2);
+    assert_eq!(fs::read_to_string(output.join("same.txt")).unwrap(), "second");
+    assert_eq!(fs::read_to_string(output.join("dir").join("file")).unwrap(), "nested");
+
+    assert!(carrier.export_all(&output, ConflictPolicy::Skip).unwrap().is_empty());
+    let paths = carrier.export_all(&output, ConflictPolicy::Rename).unwrap();
+    assert_eq!(paths, [output.join("same (1).txt"), output.join("same (2).txt"), output.join("dir").join("file (1)")]);
+    assert_eq!(fs::read_to_string(output.join("same (1).txt")).unwrap(), "first");
+    assert_eq!(fs::read_to_string(output.join("same.txt")).unwrap(), "second");
+}
+
+use crate::util::safeRelativePath;
+
+/// 释放到目录时拒绝不安全的资源文件名测试
+#[test]
+fn safeNameTest() {
+    assert_eq!(safeRelativePath("file.zip").unwrap(), PathBuf::from("file.zip"));
+    assert_eq!(safeRelativePath("img\\icons/c.ico").unwrap(), ["img", "icons", "c.ico"].iter().collect::<PathBuf>());
+    for name in ["", "../evil", "a/../../evil", "./a", "a//b", "/etc/passwd", "\\\\server\\share\\a", "C:\\Windows\\a.dll", "C:a",
+                 "file.txt:stream", "CON", "nul.txt", "a/Com1.log", "lpt9", "nul .txt", "a\u{0}b"] {
+        assert!(safeRelativePath(name).is_err(), "{}", name);
+    }
+    assert!(safeRelativePath("console.txt").is_ok());
+    // Windows 会去掉结尾的点和空格，其他系统上原样保留
+    for name in ["dir./a", "a ", " a b ", "a?b", ".hidden."] {
+        assert_eq!(safeRelativePath(name).is_err(), cfg!(windows), "{}", name);
+    }
+
+    let testPath = tempTestPath("safeName");
+    let targetPath = testPath.join("target.exe");
+    let output = testPath.join("output");
+    fs::create_dir_all(&output).unwrap();
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    let crafted = AddOptions { name: Some("..\\..\\evil.txt".to_string()), ..Default::default() };
+    carrier.add_from(&mut &b"evil"[..], "crafted", &crafted).unwrap();
+
+    let error = carrier.export("crafted", &output).unwrap_err().to_string();
+    assert!(error.contains("\"crafted\""), "{}", error);
+    assert!(crate::core::exportResourceWith(&targetPath, "crafted", &output, None).is_err());
+    assert!(carrier.export_all(&output, ConflictPolicy::Overwrite).is_err());
+    assert!(!testPath.parent().unwrap().join("evil.txt").exists());
+    assert_eq!(fs::read_dir(&output).unwrap().count(), 0);
+
+    // 指定输出文件时不使用资源文件名
+    carrier.export("crafted", &output.join("renamed.txt")).unwrap();
+
+    // 首尾带空格的文件名原样释放
+    #[cfg(not(windows))]
+    {
+        let spaced = AddOptions { name: Some(" a b ".to_string()), ..Default::default() };
+        carrier.add_from(&mut &b"spaced"[..], "spaced", &spaced).unwrap();
+        assert_eq!(carrier.export("spaced", &output).unwrap(), output.join(" a b "));
+        assert_eq!(fs::read(output.join(" a b ")).unwrap(), b"spaced");
+    }
+}
+
+/// 1.2.0 版本的资源文件头(ID、文件名居中填充空格)
+#[derive(Serialize)]
+struct PaddedHead {
+    Head: Vec<u8>,
+    Version: String,
+    Id: String,
+    Name: String,
+    Length: String,
+    Size: String,
+    Compress: CompressMode,
+    Digest: Vec<u8>,
+    Cipher: Cipher,
+    Salt: Vec<u8>,
+    Nonce: Vec<u8>,
+}
+
+/// Unicode 资源ID及文件名测试(原样保存，包括首尾空格)
+#[test]
+fn unicodeTest() {
+    let testPath = tempTestPath("unicode");
+    let targetPath = testPath.join("target.exe");
+    let id = " 资源 ID ";
+    let name = " 中文 文件名.txt ";
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    let options = AddOptions { name: Some(name.to_string()), ..Default::default() };
+    carrier.add_from(&mut "内容".as_bytes(), id, &options).unwrap();
+    assert!(carrier.find(id.trim()).unwrap().is_none());
+
+    // 通过索引及扫描读取
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    let entry = carrier.find(id).unwrap().unwrap();
+    assert_eq!((entry.head.getId(), entry.head.getName()), (id, name));
+    assert_eq!(carrier.read_to_vec(id).unwrap(), "内容".as_bytes());
+    let entries = findResources(&mut fs::File::open(&targetPath).unwrap()).unwrap();
+    assert_eq!((entries[0].head.getId(), entries[0].head.getName()), (id, name));
+
+    // 按 UTF-8 字节数限制长度
+    carrier.add_from(&mut &b"a"[..], &"中".repeat(85), &AddOptions::default()).unwrap();
+    assert!(carrier.add_from(&mut &b"a"[..], &"中".repeat(86), &AddOptions::default()).is_err());
+    let long = AddOptions { name: Some("名".repeat(342)), ..Default::default() };
+    assert!(carrier.add_from(&mut &b"a"[..], "long", &long).is_err());
+
+    // 旧版本文件头去除填充
+    let padded = PaddedHead {
+        Head: HEAD_MAGIC_V1.to_vec(),
+        Version: "1.2.0".to_string(),
+        Id: format!("{:^64}", "legacy"),
+        Name: format!("{:^255}", "legacy.txt"),
+        Length: format!("{:0>13}", 4),
+        Size: format!("{:0>13}", 4),
+        Compress: CompressMode::None,
+        Digest: vec![0; 32],
+        Cipher: Cipher::None,
+        Salt: Vec::new(),
+        Nonce: Vec::new(),
+    };
+    let bytes = bincode::serialize(&padded).unwrap();
+    let (head, headLen) = ResourceHead::parse(&bytes).unwrap();
+    assert_eq!((head.getId(), head.getName(), head.getLength()), ("legacy", "legacy.txt", 4));
+    assert_eq!(headLen, bytes.len());
+}
+
+use crate::core::HEAD_MAGIC;
+
+/// v2 二进制资源文件头测试
+#[test]
+fn headV2Test() {
+    let mut head = ResourceHead::new("资源", 9000, 12000, "source.txt", CompressMode::Compress);
+    head.setDigest(&[7; 32]);
+    head.setCipher(Cipher::Aes256Gcm, &[1; 16], &[2; 7]);
+    let bytes = head.to_bytes().unwrap();
+    assert!(bytes.starts_with(&HEAD_MAGIC));
+    assert_eq!(bytes.len(), 40 + "资源".len() + "source.txt".len() + 32 + 16 + 7 + 4);
+    assert_eq!(bytes.len(), head.getLen());
+
+    // 解析时忽略之后的数据
+    let mut data = bytes.clone();
+    data.extend(b"resource data");
+    let (parsed, headLen) = ResourceHead::parse(&data).unwrap();
+    assert_eq!(headLen, bytes.len());
+    assert_eq!((parsed.getVersion(), parsed.getId(), parsed.getName()), ("2.0.0", "资源", "source.txt"));
+    assert_eq!((parsed.getLength(), parsed.getSize(), parsed.getCompressMode()), (9000, 12000, CompressMode::Compress));
+    assert_eq!((parsed.getDigest(), parsed.getCipher()), (&[7; 32][..], Cipher::Aes256Gcm));
+
+    // 任意字节损坏或截断都会被拒绝
+    for index in 8..bytes.len() {
+        let mut corrupted = bytes.clone();
+        corrupted[index] ^= 0x01;
+        assert!(ResourceHead::parse(&corrupted).is_err());
+    }
+    assert!(ResourceHead::parse(&bytes[..bytes.len() - 1]).is_err());
+
+    // 载体中 v1、v2 资源并存
+    let testPath = tempTestPath("headV2");
+    let targetPath = testPath.join("target.exe");
+    let legacy = Astruct1_0 {
+        Head: HEAD_MAGIC_V1.to_vec(),
+        Version: "1.0.0".to_string(),
+        Id: format!("{:^64}", "legacy"),
+        Name: format!("{:^255}", "legacy.txt"),
+        Length: format!("{:0>13}", 6),
+        Size: format!("{:0>13}", 6),
+        Compress: CompressMode::None,
+    };
+    let mut data = fs::read(&targetPath).unwrap();
+    data.extend(bincode::serialize(&legacy).unwrap());
+    data.extend(b"legacyODEND");
+    fs::write(&targetPath, &data).unwrap();
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add(&testPath.join("source.txt"), "current", None).unwrap();
+    let entries = findResources(&mut fs::File::open(&targetPath).unwrap()).unwrap();
+    let versions: Vec<&str> = entries.iter().map(|entry| entry.head.getVersion()).collect();
+    assert_eq!(versions, vec!["1.0.0", "2.0.0"]);
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert_eq!(carrier.read_to_vec("legacy").unwrap(), b"legacy");
+    assert_eq!(carrier.read_to_vec("current").unwrap(), fs::read(testPath.join("source.txt")).unwrap());
+}
+
+use crate::core::{compareVersion, versionCompatibility, VersionCompatibility};
+use sha2::{Digest, Sha256};
+
+/// 资源版本兼容性测试
+#[test]
+fn versionTest() {
+    assert!(compareVersion("1.10.0", "1.9.0").unwrap().is_gt());
+    assert!(compareVersion("2.0", "2.0.0").unwrap().is_eq());
+    assert!(compareVersion("2.1.0-beta", "2.0.5").unwrap().is_gt());
+    assert!(compareVersion("1.x.0", "1.0.0").is_err());
+    assert!(versionCompatibility("").is_err());
+    assert_eq!(versionCompatibility("1.3.0").unwrap(), VersionCompatibility::Older);
+    assert_eq!(versionCompatibility("2.0.0").unwrap(), VersionCompatibility::Current);
+    assert_eq!(versionCompatibility("2.0.7").unwrap(), VersionCompatibility::Current);
+    assert_eq!(versionCompatibility("2.3.0").unwrap(), VersionCompatibility::NewerMinor);
+    assert_eq!(versionCompatibility("3.0.0").unwrap(), VersionCompatibility::Incompatible);
+
+    // 新的次版本在文件头末尾增加了字段
+    let mut head = ResourceHead::new("newer", 6, 6, "newer.txt", CompressMode::None);
+    head.setDigest(&Sha256::digest(b"newer!"));
+    let bytes = head.to_bytes().unwrap();
+    let withVersion = |version: u16| {
+        let mut data = bytes[..bytes.len() - 4].to_vec();
+        data[8..10].copy_from_slice(&version.to_le_bytes());
+        data.extend(b"future");
+        let headLen = (data.len() + 4) as u16;
+        data[10..12].copy_from_slice(&headLen.to_le_bytes());
+        let crc = crc32fast::hash(&data);
+        data.extend(crc.to_le_bytes());
+        data
+    };
+    let (parsed, headLen) = ResourceHead::parse(&withVersion(0x0203)).unwrap();
+    assert_eq!((parsed.getVersion(), parsed.getId(), headLen), ("2.3.0", "newer", bytes.len() + 6));
+    let error = ResourceHead::parse(&withVersion(0x0300)).unwrap_err();
+    assert!(error.to_string().contains("upgrade"));
+
+    let testPath = tempTestPath("version");
+    let targetPath = testPath.join("target.exe");
+    let mut data = fs::read(&targetPath).unwrap();
+    data.extend(withVersion(0x0203));
+    data.extend(b"newer!ODEND");
+    fs::write(&targetPath, &data).unwrap();
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert_eq!(carrier.read_to_vec("newer").unwrap(), b"newer!");
+}
+
+/// 迁移旧版本资源测试
+#[test]
+fn migrateTest() {
+    let testPath = tempTestPath("migrate");
+    let targetPath = testPath.join("target.exe");
+    let legacy = Astruct1_0 {
+        Head: HEAD_MAGIC_V1.to_vec(),
+        Version: "1.0.0".to_string(),
+        Id: format!("{:^64}", "legacy"),
+        Name: format!("{:^255}", "legacy.txt"),
+        Length: format!("{:0>13}", 6),
+        Size: format!("{:0>13}", 6),
+        Compress: CompressMode::None,
+    };
+    let padded = PaddedHead {
+        Head: HEAD_MAGIC_V1.to_vec(),
+        Version: "1.2.0".to_string(),
+        Id: format!("{:^64}", "padded"),
+        Name: format!("{:^255}", "padded.txt"),
+        Length: format!("{:0>13}", 6),
+        Size: format!("{:0>13}", 6),
+        Compress: CompressMode::None,
+        Digest: Sha256::digest(b"padded").to_vec(),
+        Cipher: Cipher::None,
+        Salt: Vec::new(),
+        Nonce: Vec::new(),
+    };
+    let mut data = fs::read(&targetPath).unwrap();
+    data.extend(bincode::serialize(&legacy).unwrap());
+    data.extend(b"legacyODEND");
+    data.extend(bincode::serialize(&padded).unwrap());
+    data.extend(b"paddedODEND");
+    fs::write(&targetPath, &data).unwrap();
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    let options = AddOptions { alignment: 4096, ..Default::default() };
+    carrier.add_from(&mut "aligned".as_bytes(), "aligned", &options).unwrap();
+    let padded = carrier.find("padded").unwrap().unwrap();
+    let legacyData = carrier.find("legacy").unwrap().unwrap().dataOffset();
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert_eq!(carrier.migrate().unwrap(), vec!["legacy", "padded"]);
+    assert!(carrier.find("padded").unwrap().unwrap().dataOffset() < padded.dataOffset());
+    // 第一个资源的数据位置不变
+    assert_eq!(carrier.find("legacy").unwrap().unwrap().dataOffset(), legacyData);
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    let versions: Vec<&str> = carrier.entries().unwrap().iter().map(|entry| entry.head.getVersion()).collect();
+    assert_eq!(versions, vec!["2.0.0"; 3]);
+    assert_eq!(carrier.find("aligned").unwrap().unwrap().dataOffset() % 4096, 0);
+    assert_eq!(carrier.read_to_vec("legacy").unwrap(), b"legacy");
+    assert_eq!(carrier.read_to_vec("padded").unwrap(), b"padded");
+    assert_eq!(carrier.read_to_vec("aligned").unwrap(), b"aligned");
+    assert_eq!(findResources(&mut fs::File::open(&targetPath).unwrap()).unwrap().len(), 3);
+
+    // 没有旧版本资源时不修改文件
+    let length = fs::metadata(&targetPath).unwrap().len();
+    assert!(carrier.migrate().unwrap().is_empty());
+    assert_eq!(fs::metadata(&targetPath).unwrap().len(), length);
+}
+
+/// 迁移紧跟映像的 1.0.0 版本压缩资源测试(重新计算原始大小，不留下空隙)
+#[test]
+fn migrateLegacyCompressedTest() {
+    let testPath = tempTestPath("migrateLegacyCompressed");
+    let targetPath = testPath.join("target.exe");
+    let source = "Appender ".repeat(1000).into_bytes();
+    let mut data = buildPe(b"text");
+    let imageEnd = data.len() as u64;
+    data.extend(legacyResource("gzip", "gzip.txt", &source, CompressMode::Compress));
+    data.extend(legacyResource("plain", "plain.txt", b"plain", CompressMode::None));
+    fs::write(&targetPath, &data).unwrap();
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert_eq!(carrier.migrate().unwrap(), vec!["gzip", "plain"]);
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    let entry = carrier.find("gzip").unwrap().unwrap();
+    assert_eq!(entry.offset, imageEnd);
+    assert!(entry.head.isSizeKnown());
+    assert_eq!(entry.head.getSize(), source.len() as u64);
+    assert_eq!(carrier.foreign_overlay().unwrap(), None);
+    assert_eq!(carrier.verify("gzip").unwrap(), source.len() as u64);
+    assert_eq!(carrier.read_to_vec("plain").unwrap(), b"plain");
+}
+
+use crate::core::{findResourcesFrom, BUFFER_SIZE};
+use crate::scanner::ResourceScanner;
+
+/// 生成 v2 格式的资源(文件头、数据及尾部标识)
+fn rawResource(id: &str, data: &[u8]) -> Vec<u8> {
+    let mut head = ResourceHead::new(id, data.len() as u64, data.len() as u64, id, CompressMode::None);
+    head.setDigest(&Sha256::digest(data));
+    let mut bytes = head.to_bytes().unwrap();
+    bytes.extend(data);
+    bytes.extend(b"ODEND");
+    bytes
+}
+
+/// 资源扫描测试(跨越块边界的标识、同一块中的多个资源、资源数据中的标识)
+#[test]
+fn scannerTest() {
+    let testPath = tempTestPath("scanner");
+    let targetPath = testPath.join("target.bin");
+
+    // 同一块中的两个资源，其中一个的数据包含标识
+    let mut data = vec![0u8; 100];
+    data.extend(rawResource("a", &HEAD_MAGIC));
+    data.extend(rawResource("b", b"b"));
+    // v2 标识跨越第一块与第二块
+    data.resize(BUFFER_SIZE - 3, 0);
+    data.extend(rawResource("c", b"c"));
+    // v1 标识跨越第二块与第三块(文件头在第二块中，标识前有 8 字节长度)
+    data.resize(BUFFER_SIZE * 2 - 12, 0);
+    let legacy = Astruct1_0 {
+        Head: HEAD_MAGIC_V1.to_vec(),
+        Version: "1.0.0".to_string(),
+        Id: format!("{:^64}", "d"),
+        Name: format!("{:^255}", "d"),
+        Length: format!("{:0>13}", 1),
+        Size: format!("{:0>13}", 1),
+        Compress: CompressMode::None,
+    };
+    data.extend(bincode::serialize(&legacy).unwrap());
+    data.extend(b"dODEND");
+    // 标识后不是有效的文件头
+    data.extend(HEAD_MAGIC);
+    data.extend(vec![0xFFu8; 100]);
+    data.extend(rawResource("e", b"e"));
+    fs::write(&targetPath, &data).unwrap();
+
+    let mut file = fs::File::open(&targetPath).unwrap();
+    let entries = findResourcesFrom(&mut file, 0).unwrap();
+    let ids: Vec<&str> = entries.iter().map(|entry| entry.head.getId()).collect();
+    assert_eq!(ids, vec!["a", "b", "c", "d", "e"]);
+    assert_eq!(entries[2].offset, BUFFER_SIZE as u64 - 3);
+    assert_eq!(entries[3].offset, BUFFER_SIZE as u64 * 2 - 12);
+
+    // 迭代时逐个返回，起始位置之前的资源被忽略
+    let ids: Vec<String> = ResourceScanner::new(&mut file, 101).map(|entry| entry.unwrap().head.getId().to_string()).collect();
+    assert_eq!(ids, vec!["b", "c", "d", "e"]);
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert_eq!(carrier.read_to_vec("a").unwrap(), HEAD_MAGIC);
+    assert_eq!(carrier.read_to_vec("d").unwrap(), b"d");
+    assert_eq!(listResources(&targetPath).unwrap().len(), 5);
+}
+
+use crate::error::{AppenderError, exitCode, withContext};
+use crate::core::MAX_ID_LENGTH;
+
+/// 错误类型及退出码测试
+#[test]
+fn errorTest() {
+    let testPath = tempTestPath("error");
+    let targetPath = testPath.join("target.exe");
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add(&testPath.join("source.txt"), "1", None).unwrap();
+
+    let kind = |e: Box<dyn std::error::Error>| match e.downcast_ref::<AppenderError>() {
+        Some(AppenderError::InvalidArgument(_)) => "InvalidArgument",
+        Some(AppenderError::NotFound(_)) => "NotFound",
+        Some(AppenderError::Corrupt(_)) => "Corrupt",
+        Some(AppenderError::VersionMismatch(_)) => "VersionMismatch",
+        Some(AppenderError::DuplicateId(_)) => "DuplicateId",
+        Some(AppenderError::InvalidHeader(_)) => "InvalidHeader",
+        Some(AppenderError::Io(_)) => "Io",
+        Some(AppenderError::Conflict(_)) => "Conflict",
+        None => "Other",
+    };
+    assert_eq!(kind(carrier.read_to_vec("missing").unwrap_err()), "NotFound");
+    assert_eq!(kind(carrier.add(&testPath.join("source.txt"), "1", None).unwrap_err()), "DuplicateId");
+    assert_eq!(kind(carrier.add(&testPath.join("source.txt"), "2", Some(99)).unwrap_err()), "InvalidArgument");
+    assert_eq!(kind(ResourceHead::parse(&HEAD_MAGIC).unwrap_err()), "InvalidHeader");
+    assert_eq!(kind(ResourceHead::parse(b"not a resource head").unwrap_err()), "InvalidHeader");
+    let future = futureHead(&ResourceHead::new("1", 0, 0, "1", CompressMode::None).to_bytes().unwrap());
+    assert_eq!(kind(ResourceHead::parse(&future).unwrap_err()), "VersionMismatch");
+    assert_eq!(kind(carrier.add(&testPath.join("source.txt"), &"a".repeat(MAX_ID_LENGTH + 1), None).unwrap_err()), "InvalidArgument");
+    assert_eq!(kind(carrier.export_all(&testPath, ConflictPolicy::Error).unwrap_err()), "Conflict");
+    // 添加说明后错误类型不变
+    assert_eq!(kind(withContext(carrier.read_to_vec("missing").unwrap_err(), "context")), "NotFound");
+    assert_eq!(kind(withContext(Box::from(std::io::Error::from(std::io::ErrorKind::NotFound)), "context")), "Io");
+
+    // 修改资源数据(长度不变)
+    let entry = carrier.find("1").unwrap().unwrap();
+    let mut data = fs::read(&targetPath).unwrap();
+    data[entry.dataOffset() as usize + 100] ^= 0xFF;
+    fs::write(&targetPath, &data).unwrap();
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert_eq!(kind(carrier.read_to_vec("1").unwrap_err()), "Corrupt");
+
+    // 未转换的读写错误同样视为读写失败
+    let missing = Carrier::open(testPath.join("missing.exe")).err().unwrap();
+    assert_eq!(exitCode(&*missing), AppenderError::Io(std::io::Error::from(std::io::ErrorKind::NotFound)).exit_code());
+    assert_eq!(exitCode(&*Box::<dyn std::error::Error>::from("other")), 1);
+
+    // 各错误类型的退出码不同
+    let mut codes: Vec<i32> = [
+        AppenderError::InvalidArgument(String::new()), AppenderError::NotFound(String::new()), AppenderError::Corrupt(String::new()),
+        AppenderError::VersionMismatch(String::new()), AppenderError::DuplicateId(String::new()), AppenderError::InvalidHeader(String::new()),
+        AppenderError::Io(std::io::Error::from(std::io::ErrorKind::Other)), AppenderError::Conflict(String::new()),
+    ].iter().map(|error| error.exit_code()).collect();
+    codes.push(1);
+    codes.sort_unstable();
+    codes.dedup();
+    assert_eq!(codes.len(), 9);
+}
+
+/// 生成 1.0.0 版本的资源(文件头、数据及尾部标识)
+///
+/// 与 1.0.0 版本相同，压缩资源的长度及大小均记录为压缩后的长度
+fn legacyResource(id: &str, name: &str, data: &[u8], compress: CompressMode) -> Vec<u8> {
+    let mut stored = Vec::new();
+    match compress {
+        CompressMode::None => stored.extend(data),
+        _ => { crate::util::compressionStream(&mut &data[..], &mut stored, 6).unwrap(); }
+    }
+    let legacy = Astruct1_0 {
+        Head: HEAD_MAGIC_V1.to_vec(),
+        Version: "1.0.0".to_string(),
+        Id: format!("{:^64}", id),
+        Name: format!("{:^255}", name),
+        Length: format!("{:0>13}", stored.len()),
+        Size: format!("{:0>13}", stored.len()),
+        Compress: compress,
+    };
+    let mut bytes = bincode::serialize(&legacy).unwrap();
+    bytes.extend(stored);
+    bytes.extend(b"ODEND");
+    bytes
+}
+
+/// 旧载体文件中ID重复的资源释放测试(按资源条目释放，并区分跳过与覆盖)
+#[test]
+fn duplicateLegacyIdTest() {
+    let testPath = tempTestPath("duplicateLegacyId");
+    let targetPath = testPath.join("target.exe");
+    let mut data = fs::read(&targetPath).unwrap();
+    data.extend(legacyResource("Data", "one.txt", b"AAAA", CompressMode::None));
+    data.extend(legacyResource("Data", "two.txt", b"BBBB", CompressMode::None));
+    data.extend(legacyResource("Other", "two.txt", b"CCCC", CompressMode::None));
+    fs::write(&targetPath, &data).unwrap();
+
+    let output = testPath.join("output");
+    fs::create_dir_all(&output).unwrap();
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    let report = carrier.export_directory_report("Data", &output, ConflictPolicy::Error).unwrap();
+    assert_eq!(report.exported.len(), 2);
+    assert_eq!(fs::read(output.join("one.txt")).unwrap(), b"AAAA");
+    assert_eq!(fs::read(output.join("two.txt")).unwrap(), b"BBBB");
+
+    // 跳过已存在的文件
+    let report = carrier.export_directory_report("", &output, ConflictPolicy::Skip).unwrap();
+    assert!(report.exported.is_empty());
+    assert_eq!(report.skipped.len(), 3);
+    assert!(report.overwritten.is_empty());
+
+    // 资源文件名重复时，之前的资源被覆盖
+    fs::remove_dir_all(&output).unwrap();
+    fs::create_dir_all(&output).unwrap();
+    let report = carrier.export_directory_report("", &output, ConflictPolicy::Overwrite).unwrap();
+    assert_eq!(report.exported.len(), 2);
+    assert_eq!(report.overwritten, vec![("Data".to_string(), output.join("two.txt"))]);
+    assert!(report.skipped.is_empty());
+    assert_eq!(fs::read(output.join("two.txt")).unwrap(), b"CCCC");
+}
+
+/// 1.0.0 版本压缩资源测试(资源大小记录为压缩后的长度)
+#[test]
+fn legacyCompressedTest() {
+    let testPath = tempTestPath("legacyCompressed");
+    let targetPath = testPath.join("target.exe");
+    let source = "Appender ".repeat(1000).into_bytes();
+    let mut data = fs::read(&targetPath).unwrap();
+    data.extend(legacyResource("gzip", "gzip.txt", &source, CompressMode::Compress));
+    fs::write(&targetPath, &data).unwrap();
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    let entry = carrier.find("gzip").unwrap().unwrap();
+    assert!(!entry.head.isSizeKnown());
+    assert_eq!(carrier.read_to_vec("gzip").unwrap(), source);
+    assert_eq!(carrier.verify("gzip").unwrap(), source.len() as u64);
+    carrier.export("gzip", &testPath).unwrap();
+    assert_eq!(fs::read(testPath.join("gzip.txt")).unwrap(), source);
+
+    // 读取器解压一次得到原始大小
+    let mut reader = carrier.open_resource("gzip").unwrap();
+    assert_eq!(reader.size(), source.len() as u64);
+    assert_eq!(reader.seek(SeekFrom::End(-5)).unwrap(), source.len() as u64 - 5);
+    let mut tail = Vec::new();
+    reader.read_to_end(&mut tail).unwrap();
+    assert_eq!(tail, &source[source.len() - 5..]);
+}
+
+/// 读取一定长度后失败的输入流
+struct FailingReader(usize);
+
+impl std::io::Read for FailingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.0 == 0 {
+            return Err(std::io::Error::other("source failed"));
+        }
+        let size = std::cmp::min(self.0, buf.len());
+        buf[..size].fill(b'x');
+        self.0 -= size;
+        Ok(size)
+    }
+}
+
+/// 读取输入流失败时恢复载体文件测试
+#[test]
+fn addRollbackTest() {
+    let testPath = tempTestPath("addRollback");
+    let targetPath = testPath.join("target.exe");
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    carrier.add_from(&mut &b"first"[..], "first", &AddOptions::default()).unwrap();
+    let original = fs::read(&targetPath).unwrap();
+
+    // 增加资源失败
+    assert!(carrier.add_from(&mut FailingReader(100 * 1024), "bad", &AddOptions::default()).is_err());
+    assert_eq!(fs::read(&targetPath).unwrap(), original);
+
+    // 替换资源失败时保留原资源
+    let options = AddOptions { replace: true, ..Default::default() };
+    assert!(carrier.add_from(&mut FailingReader(100 * 1024), "first", &options).is_err());
+    assert_eq!(fs::read(&targetPath).unwrap(), original);
+
+    // 之后的修改不会写入失败的资源
+    carrier.add_from(&mut &b"second"[..], "second", &AddOptions::default()).unwrap();
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    let ids: Vec<&str> = carrier.entries().unwrap().iter().map(|entry| entry.head.getId()).collect();
+    assert_eq!(ids, vec!["first", "second"]);
+    assert_eq!(carrier.read_to_vec("first").unwrap(), b"first");
+
+    // 替换资源成功
+    carrier.add_from(&mut &b"replaced"[..], "first", &options).unwrap();
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    let ids: Vec<&str> = carrier.entries().unwrap().iter().map(|entry| entry.head.getId()).collect();
+    assert_eq!(ids, vec!["second", "first"]);
+    assert_eq!(carrier.read_to_vec("first").unwrap(), b"replaced");
+    assert_eq!(carrier.read_to_vec("second").unwrap(), b"second");
+}
+
+/// 将 v2 资源文件头改为新的主版本(重新计算 CRC32)
+fn futureHead(bytes: &[u8]) -> Vec<u8> {
+    let mut future = bytes.to_vec();
+    future[9] = 3;
+    let crcOffset = future.len() - 4;
+    let crc = crc32fast::hash(&future[..crcOffset]);
+    future[crcOffset..].copy_from_slice(&crc.to_le_bytes());
+    future
+}
+
+/// 新的主版本写入的载体文件测试(返回版本不兼容的错误，而不是找不到资源)
+#[test]
+fn futureMajorTest() {
+    let testPath = tempTestPath("futureMajor");
+    let isMismatch = |e: Box<dyn std::error::Error>| matches!(e.downcast_ref::<AppenderError>(), Some(AppenderError::VersionMismatch(_)));
+
+    // 有资源索引
+    let indexedPath = testPath.join("indexed.exe");
+    fs::copy(testPath.join("target.exe"), &indexedPath).unwrap();
+    let mut carrier = Carrier::open(&indexedPath).unwrap();
+    carrier.add_from(&mut &b"future"[..], "future", &AddOptions::default()).unwrap();
+    let entry = carrier.find("future").unwrap().unwrap();
+    let mut data = fs::read(&indexedPath).unwrap();
+    let range = entry.offset as usize..entry.dataOffset() as usize;
+    let future = futureHead(&data[range.clone()]);
+    data[range].copy_from_slice(&future);
+    fs::write(&indexedPath, &data).unwrap();
+    let mut carrier = Carrier::open(&indexedPath).unwrap();
+    assert!(isMismatch(carrier.export("future", &testPath.join("output.txt")).unwrap_err()));
+    assert!(!testPath.join("output.txt").exists());
+
+    // 没有资源索引(扫描)
+    let scannedPath = testPath.join("scanned.exe");
+    let mut data = fs::read(testPath.join("target.exe")).unwrap();
+    data.extend(future);
+    data.extend(b"futureODEND");
+    fs::write(&scannedPath, &data).unwrap();
+    let mut carrier = Carrier::open(&scannedPath).unwrap();
+    assert!(isMismatch(carrier.export("future", &testPath.join("output.txt")).unwrap_err()));
+    assert!(isMismatch(listResources(&scannedPath).unwrap_err()));
+}
+
+/// 资源本身是载体文件时的扫描测试(不把资源数据中的资源当作资源)
+#[test]
+fn nestedCarrierTest() {
+    let testPath = tempTestPath("nestedCarrier");
+    let innerPath = testPath.join("inner.exe");
+    fs::copy(testPath.join("target.exe"), &innerPath).unwrap();
+    let mut inner = Carrier::open(&innerPath).unwrap();
+    inner.add(&testPath.join("source.txt"), "innerid", None).unwrap();
+    let innerData = fs::read(&innerPath).unwrap();
+
+    // 没有资源索引的载体文件(扫描)，资源之后还有一个资源
+    let targetPath = testPath.join("target.exe");
+    let mut data = fs::read(&targetPath).unwrap();
+    data.extend(rawResource("outer", &innerData));
+    data.extend(rawResource("after", b"after"));
+    fs::write(&targetPath, &data).unwrap();
+
+    let entries = findResources(&mut fs::File::open(&targetPath).unwrap()).unwrap();
+    let ids: Vec<&str> = entries.iter().map(|entry| entry.head.getId()).collect();
+    assert_eq!(ids, vec!["outer", "after"]);
+
+    let mut carrier = Carrier::open(&targetPath).unwrap();
+    assert_eq!(carrier.read_to_vec("outer").unwrap(), innerData);
+    assert_eq!(carrier.read_to_vec("after").unwrap(), b"after");
+    assert!(carrier.remove("innerid").is_err());
+    assert_eq!(listResources(&targetPath).unwrap().len(), 2);
+
+    // 资源超过一块时从资源结束位置继续读取
+    let mut large = innerData.clone();
+    large.resize(BUFFER_SIZE * 2 + 100, 0);
+    large.extend(&innerData);
+    let mut data = fs::read(testPath.join("source.txt")).unwrap();
+    data.extend(rawResource("large", &large));
+    data.extend(rawResource("after", b"after"));
+    fs::write(&targetPath, &data).unwrap();
+    let entries = findResources(&mut fs::File::open(&targetPath).unwrap()).unwrap();
+    let ids: Vec<&str> = entries.iter().map(|entry| entry.head.getId()).collect();
+    assert_eq!(ids, vec!["large", "after"]);
+}
@@ -1,11 +1,12 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::fs::File;
-use std::io::{BufReader};
+use std::io::{BufReader, Read, Write, Seek, SeekFrom};
 use std::error::Error;
 use flate2::write::GzEncoder;
 use flate2::write::GzDecoder;
 use flate2::Compression;
 use std::io::copy;
+use sha2::{Digest, Sha256};
 
 /// 压缩文件
 /// #参数
@@ -36,3 +37,171 @@ pub fn decompressFile(filePath: &Path, outputPath: &Path) -> Result<(), Box<dyn
     decoder.finish()?;
     Ok(())
 }
+
+/// 压缩数据流
+/// # 参数
+/// 1. 输入流
+/// 2. 输出流
+/// 3. 压缩等级(0-9)
+/// # 返回值
+/// 读取的原始数据字节数
+pub fn compressionStream<R: Read + ?Sized, W: Write>(input: &mut R, output: W, compressionGrade: u32) -> Result<u64, Box<dyn Error>> {
+    let mut encoder = GzEncoder::new(output, Compression::new(compressionGrade));
+    let size = copy(input, &mut encoder)?;
+    encoder.finish()?;
+    Ok(size)
+}
+
+/// 计算摘要的读取器(SHA-256)
+pub struct DigestReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> DigestReader<R> {
+    pub fn new(inner: R) -> Self {
+        DigestReader { inner, hasher: Sha256::new() }
+    }
+
+    /// 获取已读取数据的摘要
+    pub fn finalize(self) -> Vec<u8> {
+        self.hasher.finalize().to_vec()
+    }
+}
+
+impl<R: Read> Read for DigestReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let nbytes = self.inner.read(buf)?;
+        self.hasher.update(&buf[..nbytes]);
+        Ok(nbytes)
+    }
+}
+
+/// 计算摘要的写入器(SHA-256)
+pub struct DigestWriter<W> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> DigestWriter<W> {
+    pub fn new(inner: W) -> Self {
+        DigestWriter { inner, hasher: Sha256::new() }
+    }
+
+    /// 获取已写入数据的摘要
+    pub fn finalize(self) -> Vec<u8> {
+        self.hasher.finalize().to_vec()
+    }
+}
+
+impl<W: Write> Write for DigestWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let nbytes = self.inner.write(buf)?;
+        self.hasher.update(&buf[..nbytes]);
+        Ok(nbytes)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// 读取文件指定位置的字节
+/// # 参数
+/// 1. 文件
+/// 2. 起始位置
+/// 3. 长度
+pub fn readAt(file: &mut File, offset: u64, length: usize) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut buffer = vec![0u8; length];
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// 按字节序解析无符号整数
+/// # 参数
+/// 1. 数据
+/// 2. 起始位置
+/// 3. 整数长度(1、2、4、8 字节)
+/// 4. 是否为大端序
+pub fn readUInt(data: &[u8], offset: usize, size: usize, bigEndian: bool) -> u64 {
+    let bytes = &data[offset..offset + size];
+    if bigEndian {
+        bytes.iter().fold(0u64, |value, byte| (value << 8) | *byte as u64)
+    } else {
+        bytes.iter().rev().fold(0u64, |value, byte| (value << 8) | *byte as u64)
+    }
+}
+
+/// 递归列出目录下的所有文件(按路径排序)
+///
+/// 指向文件的符号链接按文件处理，指向目录的符号链接会被跳过(避免循环链接导致无限递归)
+/// # 返回值
+/// (文件路径, 以 "/" 分隔的相对路径)
+pub fn listFiles(directory: &Path) -> Result<Vec<(PathBuf, String)>, Box<dyn Error>> {
+    let mut files = Vec::new();
+    let mut directories = vec![directory.to_path_buf()];
+    while let Some(current) = directories.pop() {
+        for item in std::fs::read_dir(&current)? {
+            let item = item?;
+            let path = item.path();
+            if item.file_type()?.is_dir() {
+                directories.push(path);
+            } else if path.is_file() {
+                let relative = path.strip_prefix(directory)?.components()
+                    .map(|component| component.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>().join("/");
+                files.push((path, relative));
+            }
+        }
+    }
+    files.sort_by(|a, b| a.1.cmp(&b.1));
+    Ok(files)
+}
+
+/// Windows 保留的设备名(不区分大小写，带扩展名时同样无法作为文件名)
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 将资源文件名转换为安全的相对路径("/" 及 "\" 均视为路径分隔符)
+///
+/// 拒绝空文件名、绝对路径、UNC 路径、盘符、"." 及 ".."、NUL 字符以及 Windows 保留的设备名，保证释放时不会写出到输出目录之外。
+/// Windows 上还拒绝控制字符、`<>"|?*` 以及以点或空格结尾的部分(系统会去掉结尾的点和空格，可能与其他文件重名)，
+/// 其他系统上首尾的空格和点原样保留
+pub fn safeRelativePath(name: &str) -> Result<PathBuf, Box<dyn Error>> {
+    if name.is_empty() {
+        return Err(Box::from("the file name is empty"));
+    }
+    if name.starts_with('/') || name.starts_with('\\') {
+        return Err(Box::from("absolute paths are not allowed"));
+    }
+    let mut path = PathBuf::new();
+    for part in name.split(['/', '\\']) {
+        if part.is_empty() || part == "." || part == ".." {
+            return Err(Box::from(format!("the path component \"{}\" is not allowed", part)));
+        }
+        if part.contains(':') {
+            return Err(Box::from("drive prefixes and \":\" are not allowed"));
+        }
+        if part.contains('\0') {
+            return Err(Box::from(format!("the path component \"{}\" contains a NUL character", part.escape_default())));
+        }
+        if cfg!(windows) {
+            if part.chars().any(|c| c.is_control() || matches!(c, '<' | '>' | '"' | '|' | '?' | '*')) {
+                return Err(Box::from(format!("the path component \"{}\" contains invalid characters", part)));
+            }
+            if part.ends_with('.') || part.ends_with(' ') {
+                return Err(Box::from(format!("the path component \"{}\" ends with a dot or space", part)));
+            }
+        }
+        let stem = part.split('.').next().unwrap().trim_end();
+        if RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(stem)) {
+            return Err(Box::from(format!("\"{}\" is a reserved device name", part)));
+        }
+        path.push(part);
+    }
+    Ok(path)
+}
@@ -7,3 +7,11 @@ pub fn is_valid_path(path: String) -> Result<(), String> {
     };
     Ok(())
 }
+
+/// 是否为有效的输入路径("-" 表示标准输入)
+pub fn is_valid_input(path: String) -> Result<(), String> {
+    if path == "-" {
+        return Ok(());
+    }
+    is_valid_path(path)
+}